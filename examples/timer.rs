@@ -1,5 +1,3 @@
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use minuteurs::Timer;
@@ -16,27 +14,21 @@ fn main() {
     let mut watcher2 = watcher1.clone();
 
     let now = Instant::now();
-    let stop = Arc::new(AtomicBool::default());
 
     // Spawn two threads.
     // They should prints approximatively every 1s.
-    let stop_clone = Arc::clone(&stop);
     let thread1 = std::thread::spawn(move || {
-        while !stop_clone.load(Ordering::SeqCst) {
-            if watcher1.has_ticked() {
-                let elapsed = now.elapsed();
-                println!("thread1 ticked at {elapsed:?}",)
-            }
+        // Parks the thread until the timer ticks, and stops once the timer is dropped.
+        while watcher1.wait().is_ok() {
+            let elapsed = now.elapsed();
+            println!("thread1 ticked at {elapsed:?}",)
         }
     });
 
-    let stop_clone = Arc::clone(&stop);
     let thread2 = std::thread::spawn(move || {
-        while !stop_clone.load(Ordering::SeqCst) {
-            if watcher2.has_ticked() {
-                let elapsed = now.elapsed();
-                println!("thread2 ticked at {elapsed:?}",)
-            }
+        while watcher2.wait().is_ok() {
+            let elapsed = now.elapsed();
+            println!("thread2 ticked at {elapsed:?}",)
         }
     });
 
@@ -44,7 +36,8 @@ fn main() {
         timer.tick();
     }
 
-    stop.store(true, Ordering::SeqCst);
+    // Dropping the timer disconnects the watchers.
+    drop(timer);
 
     // Obligatory clean up.
     let _ = thread1.join();
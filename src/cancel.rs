@@ -0,0 +1,187 @@
+//! The [`CancelToken`] implementation.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::time::Instant;
+
+use crate::Clock;
+
+/* ---------- */
+
+/// A handle interrupting the waits of [`Deadline::wait_cancellable()`](crate::Deadline::wait_cancellable)
+/// from another thread, e.g. during an application's shutdown.
+///
+/// Tokens are cheaply clonable, cancelling a token cancels all its clones. Once cancelled,
+/// a token stays cancelled.
+#[derive(Debug, Default, Clone)]
+pub struct CancelToken {
+    /// The state shared by the token's clones.
+    inner: Arc<CancelInner>,
+}
+
+impl CancelToken {
+    /// Returns a new token that isn't cancelled.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the token, interrupting the waits in progress and the next ones at once.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::Release);
+
+        // Taking the lock guarantees that every sleeping thread either waits on
+        // the condvar or will see the cancellation.
+        drop(
+            self.inner
+                .lock
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        self.inner.cond.notify_all();
+    }
+
+    /// Returns whether or not the token has been cancelled.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Blocks the current thread until `clock` reaches `deadline` or the token is cancelled.
+    ///
+    /// The thread sleeps on the token in real time, then lets `clock` reach the deadline.
+    pub(crate) fn sleep_until(
+        &self,
+        clock: &(impl Clock + ?Sized),
+        deadline: Instant,
+    ) -> Result<(), Cancelled> {
+        let mut guard = self
+            .inner
+            .lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        loop {
+            if self.is_cancelled() {
+                return Err(Cancelled);
+            }
+
+            let remaining = deadline.saturating_duration_since(clock.now());
+
+            if remaining.is_zero() {
+                return Ok(());
+            }
+
+            let (next, result) = self
+                .inner
+                .cond
+                .wait_timeout(guard, remaining)
+                .unwrap_or_else(PoisonError::into_inner);
+
+            if result.timed_out() && !self.is_cancelled() {
+                drop(next);
+                clock.sleep_until(deadline);
+                return Ok(());
+            }
+
+            guard = next;
+        }
+    }
+}
+
+/// The state shared by a [`CancelToken`]'s clones.
+#[derive(Debug, Default)]
+struct CancelInner {
+    /// Whether or not the token has been cancelled, checked without locking by the busy loops.
+    cancelled: AtomicBool,
+
+    /// The lock associated to `cond`.
+    lock: Mutex<()>,
+
+    /// Wakes the sleeping threads up on cancellation.
+    cond: Condvar,
+}
+
+/* ---------- */
+
+/// The error returned by [`Deadline::wait_cancellable()`](crate::Deadline::wait_cancellable)
+/// when the [`CancelToken`] has been cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "the wait has been cancelled")
+    }
+}
+
+impl Error for Cancelled {}
+
+/* ---------- */
+
+#[cfg(test)]
+mod tests {
+    use std::thread::JoinHandle;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{Deadline, ManualClock, MissedTickBehavior, WaitStrategy};
+
+    /// Spawns a thread cancelling `token` after 50ms.
+    fn cancel_later(token: &CancelToken) -> JoinHandle<()> {
+        let token = token.clone();
+
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            token.cancel();
+        })
+    }
+
+    #[test]
+    fn wait_cancellable() {
+        let clock = ManualClock::new();
+        let behavior = MissedTickBehavior::Burst;
+        let mut deadline =
+            Deadline::repeat_with_clock(Duration::from_millis(20), behavior, clock.clone());
+        let token = CancelToken::new();
+
+        assert_eq!(deadline.wait_cancellable(&token), Ok(()));
+        assert_eq!(clock.elapsed(), Duration::from_millis(20));
+        assert_eq!(deadline.remaining_duration(), Duration::from_millis(20));
+
+        token.cancel();
+        assert!(token.is_cancelled());
+        assert_eq!(deadline.wait_cancellable(&token), Err(Cancelled));
+        assert_eq!(clock.elapsed(), Duration::from_millis(20));
+
+        // A manual clock jumps straight to the deadline rather than sleeping in real time.
+        let mut deadline = Deadline::once_with_clock(Duration::from_secs(3600), clock.clone());
+        let now = Instant::now();
+        assert_eq!(deadline.wait_cancellable(&CancelToken::new()), Ok(()));
+        assert_eq!(clock.elapsed(), Duration::from_millis(3_600_020));
+        assert!(now.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn cancel() {
+        let mut deadline = Deadline::once(Duration::from_secs(3600));
+        let token = CancelToken::new();
+        let now = Instant::now();
+
+        let cancel_thread = cancel_later(&token);
+        assert_eq!(deadline.wait_cancellable(&token), Err(Cancelled));
+        assert!(now.elapsed() < Duration::from_secs(1));
+        assert!(!deadline.expired());
+        cancel_thread.join().unwrap();
+
+        let mut deadline = Deadline::once(Duration::from_secs(3600));
+        deadline.set_wait_strategy(WaitStrategy::Spin);
+        let token = CancelToken::new();
+
+        let cancel_thread = cancel_later(&token);
+        assert_eq!(deadline.wait_cancellable(&token), Err(Cancelled));
+        cancel_thread.join().unwrap();
+    }
+}
@@ -0,0 +1,339 @@
+//! The [`Clock`] abstraction and its implementations.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::{CancelToken, Cancelled};
+
+/* ---------- */
+
+/// A source of time used by the [`Deadline`](crate::Deadline) and [`Timer`](crate::Timer) types
+/// to know the current time and to block the thread.
+pub trait Clock {
+    /// Returns the current time.
+    fn now(&self) -> Instant;
+
+    /// Blocks the current thread until `deadline` is reached.
+    ///
+    /// Returns immediately if `deadline` is in the past.
+    fn sleep_until(&self, deadline: Instant);
+
+    /// Blocks the current thread until `deadline` is reached or `token` is cancelled,
+    /// see [`Deadline::wait_cancellable()`](crate::Deadline::wait_cancellable).
+    ///
+    /// The default implementation sleeps on the token in real time before calling
+    /// [`Clock::sleep_until()`], which suits the clocks moving forward on their own.
+    /// Other clocks should override it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Cancelled`] if `token` is cancelled before `deadline` is reached.
+    fn sleep_until_cancellable(
+        &self,
+        deadline: Instant,
+        token: &CancelToken,
+    ) -> Result<(), Cancelled> {
+        token.sleep_until(self, deadline)
+    }
+}
+
+/* ---------- */
+
+/// The default [`Clock`], backed by [`Instant::now()`] and [`std::thread::sleep()`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    #[inline]
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    #[inline]
+    fn sleep_until(&self, deadline: Instant) {
+        let dur = deadline.saturating_duration_since(Instant::now());
+
+        if dur > Duration::ZERO {
+            std::thread::sleep(dur)
+        }
+    }
+}
+
+/* ---------- */
+
+/// A [`Clock`] that only moves forward when told to, mostly useful for testing.
+///
+/// Sleeping on a manual clock never blocks: the clock jumps straight to the
+/// requested instant instead, cancellable sleeps included.
+///
+/// Manual clocks are clonable, cloned ones share the same time.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    /// The time the clock was created at.
+    origin: Instant,
+
+    /// The number of nanoseconds elapsed since `origin`.
+    elapsed: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    /// Returns a new manual clock.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+            elapsed: Arc::default(),
+        }
+    }
+
+    /// Moves the clock forward by `dur`.
+    #[inline]
+    pub fn advance(&self, dur: Duration) {
+        let nanos = u64::try_from(dur.as_nanos()).unwrap_or(u64::MAX);
+        let _ = self
+            .elapsed
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |elapsed| {
+                Some(elapsed.saturating_add(nanos))
+            });
+    }
+
+    /// Returns the time elapsed since the creation of the clock.
+    #[inline]
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.elapsed.load(Ordering::Acquire))
+    }
+}
+
+impl Default for ManualClock {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    #[inline]
+    fn now(&self) -> Instant {
+        self.origin + self.elapsed()
+    }
+
+    #[inline]
+    fn sleep_until(&self, deadline: Instant) {
+        let target = deadline.saturating_duration_since(self.origin);
+        let nanos = u64::try_from(target.as_nanos()).unwrap_or(u64::MAX);
+
+        self.elapsed.fetch_max(nanos, Ordering::AcqRel);
+    }
+
+    #[inline]
+    fn sleep_until_cancellable(
+        &self,
+        deadline: Instant,
+        token: &CancelToken,
+    ) -> Result<(), Cancelled> {
+        if token.is_cancelled() {
+            return Err(Cancelled);
+        }
+
+        self.sleep_until(deadline);
+        Ok(())
+    }
+}
+
+/* ---------- */
+
+/// A [`Clock`] that sleeps until absolute points in time of the system's monotonic clock.
+///
+/// Unlike the [`SystemClock`], which converts the deadline into a relative duration before sleeping,
+/// this clock relies on `clock_nanosleep(CLOCK_MONOTONIC, TIMER_ABSTIME)`. The deadline is thus unaffected
+/// by the time spent between its computation and the actual sleep, which keeps periodic
+/// deadlines free of drift. Sleeps interrupted by a signal are resumed until the deadline is reached.
+#[cfg(all(target_os = "linux", feature = "abstime"))]
+#[derive(Debug, Clone, Copy)]
+pub struct MonotonicClock {
+    /// The time the clock was created at.
+    origin: Instant,
+
+    /// The value of the `CLOCK_MONOTONIC` clock at `origin`.
+    origin_mono: Duration,
+}
+
+#[cfg(all(target_os = "linux", feature = "abstime"))]
+impl MonotonicClock {
+    /// Returns a new monotonic clock.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+            origin_mono: monotonic_now(),
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "abstime"))]
+impl Default for MonotonicClock {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "abstime"))]
+impl Clock for MonotonicClock {
+    #[inline]
+    fn now(&self) -> Instant {
+        self.origin + monotonic_now().saturating_sub(self.origin_mono)
+    }
+
+    fn sleep_until(&self, deadline: Instant) {
+        let target = self.origin_mono + deadline.saturating_duration_since(self.origin);
+        let ts = libc::timespec {
+            tv_sec: target.as_secs() as libc::time_t,
+            tv_nsec: target.subsec_nanos() as libc::c_long,
+        };
+
+        loop {
+            // SAFETY: `ts` is a valid timespec and the remaining time is not requested
+            // for absolute sleeps.
+            let ret = unsafe {
+                libc::clock_nanosleep(
+                    libc::CLOCK_MONOTONIC,
+                    libc::TIMER_ABSTIME,
+                    &ts,
+                    std::ptr::null_mut(),
+                )
+            };
+
+            // The target being absolute, the sleep can simply be resumed on interruptions.
+            if ret != libc::EINTR {
+                break;
+            }
+        }
+    }
+}
+
+/// Returns the current value of the `CLOCK_MONOTONIC` clock.
+#[cfg(all(target_os = "linux", any(feature = "abstime", feature = "timerfd")))]
+#[inline]
+pub(crate) fn monotonic_now() -> Duration {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+
+    // SAFETY: `ts` is a valid timespec and CLOCK_MONOTONIC is always supported on Linux.
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts) };
+    Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+}
+
+/* ---------- */
+
+#[cfg(test)]
+mod system {
+    use super::*;
+
+    #[test]
+    fn sleep_until() {
+        let clock = SystemClock;
+        let now = clock.now();
+
+        clock.sleep_until(now + Duration::from_millis(50));
+        assert!(now.elapsed() >= Duration::from_millis(50));
+
+        let now = clock.now();
+        clock.sleep_until(now - Duration::from_millis(50));
+        assert!(now.elapsed() < Duration::from_millis(5));
+    }
+}
+
+#[cfg(test)]
+mod manual {
+    use super::*;
+
+    #[test]
+    fn advance() {
+        let clock = ManualClock::new();
+        let now = clock.now();
+        assert_eq!(clock.elapsed(), Duration::ZERO);
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(clock.now() - now, Duration::from_secs(1));
+
+        let clone = clock.clone();
+        clone.advance(Duration::from_secs(1));
+        assert_eq!(clock.elapsed(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn sleep_until() {
+        let clock = ManualClock::new();
+        let now = clock.now();
+
+        clock.sleep_until(now + Duration::from_secs(3600));
+        assert_eq!(clock.now() - now, Duration::from_secs(3600));
+
+        // Sleeping in the past never moves the clock backward.
+        clock.sleep_until(now);
+        assert_eq!(clock.now() - now, Duration::from_secs(3600));
+    }
+}
+
+#[cfg(all(test, target_os = "linux", feature = "abstime"))]
+mod monotonic {
+    use super::*;
+    use crate::{Deadline, MissedTickBehavior};
+
+    #[test]
+    fn now() {
+        let clock = MonotonicClock::new();
+        let now = clock.now();
+
+        std::thread::sleep(Duration::from_millis(50));
+        let elapsed = clock.now() - now;
+        assert!(
+            elapsed >= Duration::from_millis(50),
+            "elapsed = {elapsed:?}"
+        );
+    }
+
+    #[test]
+    fn sleep_until() {
+        let clock = MonotonicClock::new();
+        let now = clock.now();
+
+        clock.sleep_until(now + Duration::from_millis(50));
+        assert!(clock.now() - now >= Duration::from_millis(50));
+
+        let now = clock.now();
+        clock.sleep_until(now - Duration::from_millis(50));
+        assert!(clock.now() - now < Duration::from_millis(50));
+    }
+
+    #[test]
+    fn repeat_phase() {
+        let clock = MonotonicClock::new();
+        let start = clock.now();
+        let mut deadline = Deadline::repeat_with_clock(
+            Duration::from_millis(10),
+            MissedTickBehavior::Burst,
+            clock,
+        );
+        let first = deadline.next_delivery();
+
+        // However late the thread wakes up, the deliveries stay on the initial grid.
+        for count in 1..=10 {
+            deadline.wait();
+            assert_eq!(
+                deadline.next_delivery(),
+                first + Duration::from_millis(10) * count
+            );
+        }
+
+        let elapsed = clock.now() - start;
+        assert!(
+            elapsed >= Duration::from_millis(100),
+            "elapsed = {elapsed:?}"
+        );
+    }
+}
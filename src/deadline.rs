@@ -1,64 +1,530 @@
 //! The [`Deadline`] implementation.
 
 use std::fmt::{Debug, Formatter, Result as FmtResult};
-use std::time::{Duration, Instant};
+#[cfg(feature = "async")]
+use std::future::Future;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+#[cfg(feature = "async")]
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "async")]
+use crate::sleeper::{self, WakeId};
+#[cfg(feature = "stats")]
+use crate::Stats;
+use crate::{CancelToken, Cancelled, Clock, SystemClock, WaitStrategy};
 
 /* ---------- */
 
 /// A deadline that can either be triggered once or multiple times.
+///
+/// Deadlines measure time through a [`Clock`], the [`SystemClock`] by default.
 #[derive(Debug, Clone, Copy)]
-pub struct Deadline {
+pub struct Deadline<C = SystemClock> {
     /// The kind of deadline.
     kind: DeadlineKind,
+
+    /// The clock used to measure time.
+    clock: C,
+
+    /// How the thread is blocked until the deadline expires.
+    strategy: WaitStrategy,
+
+    /// The statistics of the deadline's wakes.
+    #[cfg(feature = "stats")]
+    stats: Stats,
 }
 
 impl Deadline {
     /// Returns a new [`Deadline`] that will be triggered only once.
     #[inline]
     pub fn once(dur: Duration) -> Self {
-        Self {
-            kind: DeadlineKind::once(dur),
-        }
+        Self::once_with_clock(dur, SystemClock)
     }
 
     /// Returns a new [`Deadline`] that can be periodically triggered.
+    ///
+    /// Missed ticks are caught up in bursts, see [`MissedTickBehavior::Burst`].
     #[inline]
     pub fn repeat(dur: Duration) -> Self {
+        Self::repeat_with(dur, MissedTickBehavior::Burst)
+    }
+
+    /// Returns a new [`Deadline`] that can be periodically triggered and that handles
+    /// missed ticks according to `behavior`.
+    #[inline]
+    pub fn repeat_with(dur: Duration, behavior: MissedTickBehavior) -> Self {
+        Self::repeat_with_clock(dur, behavior, SystemClock)
+    }
+
+    /// Returns a new [`Deadline`] that will be triggered only once, at `instant`.
+    ///
+    /// The deadline is already expired if `instant` is in the past.
+    #[inline]
+    pub fn at(instant: Instant) -> Self {
+        Self::at_with_clock(instant, SystemClock)
+    }
+
+    /// Returns a new [`Deadline`] that will be triggered only once, at the wall-clock time `time`.
+    ///
+    /// The wall-clock time is converted into an [`Instant`] on creation, so later adjustments
+    /// of the system time are not taken into account. The deadline is already expired if `time`
+    /// is in the past.
+    #[inline]
+    pub fn at_system_time(time: SystemTime) -> Self {
+        let now = Instant::now();
+        let instant = match time.duration_since(SystemTime::now()) {
+            Ok(remaining) => checked_delivery_time(now, remaining),
+            Err(err) => now.checked_sub(err.duration()).unwrap_or(now),
+        };
+
+        Self::at(instant)
+    }
+
+    /// Returns a new [`Deadline`] that can be periodically triggered at `start + k * period`.
+    ///
+    /// The first trigger is the first of these points that is not in the past, so deadlines
+    /// sharing the same `start` and `period` are in phase no matter when they were created.
+    /// Missed ticks are caught up in bursts, see [`MissedTickBehavior::Burst`].
+    #[inline]
+    pub fn repeat_from(start: Instant, period: Duration) -> Self {
+        Self::repeat_from_with_clock(start, period, MissedTickBehavior::Burst, SystemClock)
+    }
+
+    /// Returns a new [`Deadline`] that can be periodically triggered on the wall-clock boundaries
+    /// `UNIX_EPOCH + offset + k * period`, e.g. on every whole second with a period of one second.
+    ///
+    /// The first trigger is the first boundary that is not in the past. Each trigger schedules
+    /// the next one from the system time, so the deadline re-aligns itself on the boundaries when
+    /// the wall clock is stepped. Missed boundaries are skipped, see [`MissedTickBehavior::Skip`].
+    ///
+    /// A null `period` can't be aligned, such a deadline is always expired.
+    #[inline]
+    pub fn aligned(period: Duration, offset: Duration) -> Self {
+        Self {
+            kind: DeadlineKind::Repeat(DeadlineRepeat::aligned(period, offset)),
+            clock: SystemClock,
+            strategy: WaitStrategy::default(),
+            #[cfg(feature = "stats")]
+            stats: Stats::new(),
+        }
+    }
+
+    /// Returns a future that completes when the [`Deadline`] expires.
+    ///
+    /// This is the asynchronous counterpart of [`Deadline::wait()`]. It doesn't depend on
+    /// any specific runtime: expirations are driven by a lazily spawned background thread.
+    #[cfg(feature = "async")]
+    #[inline]
+    pub fn wait_async(&mut self) -> WaitAsync<'_> {
+        WaitAsync {
+            deadline: self,
+            registered: None,
+        }
+    }
+}
+
+impl<C: Clock> Deadline<C> {
+    /// Returns a new [`Deadline`] that will be triggered only once, measuring time with `clock`.
+    #[inline]
+    pub fn once_with_clock(dur: Duration, clock: C) -> Self {
+        Self {
+            kind: DeadlineKind::once(clock.now(), dur),
+            clock,
+            strategy: WaitStrategy::default(),
+            #[cfg(feature = "stats")]
+            stats: Stats::new(),
+        }
+    }
+
+    /// Returns a new [`Deadline`] that can be periodically triggered, measuring time with `clock`
+    /// and handling missed ticks according to `behavior`.
+    #[inline]
+    pub fn repeat_with_clock(dur: Duration, behavior: MissedTickBehavior, clock: C) -> Self {
+        Self {
+            kind: DeadlineKind::repeat(clock.now(), dur, behavior),
+            clock,
+            strategy: WaitStrategy::default(),
+            #[cfg(feature = "stats")]
+            stats: Stats::new(),
+        }
+    }
+
+    /// Returns a new [`Deadline`] that will be triggered only once at `instant`, measuring time
+    /// with `clock`.
+    #[inline]
+    pub fn at_with_clock(instant: Instant, clock: C) -> Self {
+        Self {
+            kind: DeadlineKind::Once(DeadlineOnce {
+                delivery_time: instant,
+            }),
+            clock,
+            strategy: WaitStrategy::default(),
+            #[cfg(feature = "stats")]
+            stats: Stats::new(),
+        }
+    }
+
+    /// Returns a new [`Deadline`] that can be periodically triggered at `start + k * period`,
+    /// measuring time with `clock` and handling missed ticks according to `behavior`.
+    ///
+    /// See [`Deadline::repeat_from()`].
+    #[inline]
+    pub fn repeat_from_with_clock(
+        start: Instant,
+        period: Duration,
+        behavior: MissedTickBehavior,
+        clock: C,
+    ) -> Self {
         Self {
-            kind: DeadlineKind::repeat(dur),
+            kind: DeadlineKind::Repeat(DeadlineRepeat::from(start, clock.now(), period, behavior)),
+            clock,
+            strategy: WaitStrategy::default(),
+            #[cfg(feature = "stats")]
+            stats: Stats::new(),
         }
     }
 
+    /// Returns the clock used by the [`Deadline`].
+    #[inline]
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Returns how the [`Deadline`] blocks the thread until it expires.
+    #[inline]
+    pub fn wait_strategy(&self) -> WaitStrategy {
+        self.strategy
+    }
+
+    /// Sets how the [`Deadline`] blocks the thread until it expires.
+    ///
+    /// Deadlines use [`WaitStrategy::Sleep`] by default.
+    #[inline]
+    pub fn set_wait_strategy(&mut self, strategy: WaitStrategy) {
+        self.strategy = strategy;
+    }
+
     /// Returns whether or not the [`Deadline`] expired.
+    ///
+    /// An expired repeatable deadline is rescheduled to its next trigger, use [`Deadline::is_due()`]
+    /// to inspect the deadline without consuming its trigger.
     #[inline]
     pub fn expired(&mut self) -> bool {
         match &mut self.kind {
-            DeadlineKind::Once(deadline) => deadline.expired(),
-            DeadlineKind::Repeat(deadline) => deadline.expired(),
+            DeadlineKind::Once(deadline) => deadline.expired(&self.clock),
+            DeadlineKind::Repeat(deadline) => deadline.expired(&self.clock),
         }
     }
 
     /// Returns the remaining duration before expiration.
+    ///
+    /// An expired repeatable deadline is rescheduled to its next trigger, use
+    /// [`Deadline::peek_remaining()`] to inspect the deadline without consuming its trigger.
     #[inline]
     pub fn remaining_duration(&mut self) -> Duration {
         match &mut self.kind {
-            DeadlineKind::Once(deadline) => deadline.remaining_duration(),
-            DeadlineKind::Repeat(deadline) => deadline.remaining_duration(),
+            DeadlineKind::Once(deadline) => deadline.remaining_duration(&self.clock),
+            DeadlineKind::Repeat(deadline) => deadline.remaining_duration(&self.clock),
+        }
+    }
+
+    /// Returns the time the [`Deadline`] is triggered at next.
+    ///
+    /// This has no side effect, the time is in the past if the deadline is due.
+    #[inline]
+    pub fn next_delivery(&self) -> Instant {
+        self.delivery_time()
+    }
+
+    /// Returns the remaining duration before expiration, [`Duration::ZERO`] if the [`Deadline`] is due.
+    ///
+    /// Unlike [`Deadline::remaining_duration()`], this never reschedules the deadline.
+    #[inline]
+    pub fn peek_remaining(&self) -> Duration {
+        self.delivery_time()
+            .saturating_duration_since(self.clock.now())
+    }
+
+    /// Returns whether or not the [`Deadline`] is due, i.e. if waiting on it wouldn't block.
+    ///
+    /// Unlike [`Deadline::expired()`], this never reschedules the deadline.
+    #[inline]
+    pub fn is_due(&self) -> bool {
+        self.clock.now() >= self.delivery_time()
+    }
+
+    /// Consumes the trigger of a due [`Deadline`] without blocking, scheduling the next one
+    /// as [`Deadline::wait()`] would.
+    ///
+    /// Returns whether or not the deadline was due. A deadline triggered only once stays due
+    /// once it expired.
+    #[inline]
+    pub fn consume(&mut self) -> bool {
+        let now = self.clock.now();
+
+        if now < self.delivery_time() {
+            return false;
         }
+
+        if let DeadlineKind::Repeat(deadline) = &mut self.kind {
+            deadline.catch_up(now);
+        }
+
+        true
     }
 
     /// Block the thread until the [`Deadline`] expires.
     #[inline]
     pub fn wait(&mut self) {
+        let strategy = self.strategy;
+
+        // The wait can't fail without a cancellation token.
+        let _ = self.wait_with(|clock, delivery_time| {
+            strategy.wait_until(clock, delivery_time);
+            Ok(())
+        });
+    }
+
+    /// Block the thread until the [`Deadline`] expires or `token` is cancelled.
+    ///
+    /// Unlike [`Deadline::wait()`], the wait is interrupted at once when another thread cancels
+    /// the token, in which case the deadline is left untouched so it can be waited on again.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Cancelled`] if `token` is cancelled before the deadline expires, or if it
+    /// already was.
+    pub fn wait_cancellable(&mut self, token: &CancelToken) -> Result<(), Cancelled> {
+        if token.is_cancelled() {
+            return Err(Cancelled);
+        }
+
+        let strategy = self.strategy;
+        self.wait_with(|clock, delivery_time| {
+            strategy.wait_until_cancellable(clock, delivery_time, token)
+        })
+    }
+
+    /// Blocks the thread with `wait_until` until the [`Deadline`] expires, then schedules
+    /// the next trigger unless the wait failed.
+    #[inline]
+    fn wait_with(
+        &mut self,
+        wait_until: impl FnOnce(&C, Instant) -> Result<(), Cancelled>,
+    ) -> Result<(), Cancelled> {
+        #[cfg(feature = "stats")]
+        let (delivery_time, burst) = {
+            let delivery_time = self.delivery_time();
+            (delivery_time, self.clock.now() >= delivery_time)
+        };
+
+        match &mut self.kind {
+            DeadlineKind::Once(deadline) => deadline.wait(&self.clock, wait_until)?,
+            DeadlineKind::Repeat(deadline) => deadline.wait(&self.clock, wait_until)?,
+        }
+
+        #[cfg(feature = "stats")]
+        self.record(delivery_time, self.clock.now(), burst);
+
+        Ok(())
+    }
+
+    /// Returns the statistics about how late the [`Deadline`] woke up.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// Clears the statistics of the [`Deadline`].
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn reset_stats(&mut self) {
+        self.stats.reset();
+    }
+
+    /// Records a wake of the [`Deadline`] at `now` for an expiration at `delivery_time`.
+    ///
+    /// `burst` tells whether the deadline already expired before the wait.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub(crate) fn record(&mut self, delivery_time: Instant, now: Instant, burst: bool) {
+        let late = now.saturating_duration_since(delivery_time);
+        let missed = self.skipped_ticks();
+
+        self.stats.record(late, burst, missed);
+    }
+
+    /// Returns the period of a repeatable [`Deadline`], or `None` if the deadline is triggered only once.
+    #[inline]
+    pub fn period(&self) -> Option<Duration> {
+        match &self.kind {
+            DeadlineKind::Once(_) => None,
+            DeadlineKind::Repeat(deadline) => Some(deadline.dur),
+        }
+    }
+
+    /// Changes the period of a repeatable [`Deadline`].
+    ///
+    /// The new period takes effect at the next trigger, which is rescheduled one new period
+    /// after the previous trigger so the phase of the deadline is kept. If that time already
+    /// passed, the next trigger is the first one of the new period's grid that isn't in the past,
+    /// rather than a burst catching up with it. Aligned deadlines are re-aligned on the
+    /// boundaries of the new period instead, see [`Deadline::aligned()`].
+    ///
+    /// This has no effect on deadlines triggered only once.
+    #[inline]
+    pub fn set_period(&mut self, period: Duration) {
+        if let DeadlineKind::Repeat(deadline) = &mut self.kind {
+            deadline.set_period(period, self.clock.now())
+        }
+    }
+
+    /// Returns the time when the [`Deadline`] is triggered.
+    #[inline]
+    pub(crate) fn delivery_time(&self) -> Instant {
+        match &self.kind {
+            DeadlineKind::Once(deadline) => deadline.delivery_time,
+            DeadlineKind::Repeat(deadline) => deadline.delivery_time,
+        }
+    }
+
+    /// Reschedules the next trigger of the [`Deadline`] at `delivery_time`.
+    #[inline]
+    pub(crate) fn reschedule(&mut self, delivery_time: Instant) {
         match &mut self.kind {
-            DeadlineKind::Once(deadline) => deadline.wait(),
-            DeadlineKind::Repeat(deadline) => deadline.wait(),
+            DeadlineKind::Once(deadline) => deadline.delivery_time = delivery_time,
+            DeadlineKind::Repeat(deadline) => deadline.delivery_time = delivery_time,
+        }
+    }
+
+    /// Schedules the next trigger of an aligned [`Deadline`] on the wall-clock boundary
+    /// following the current one, see [`Deadline::aligned()`].
+    ///
+    /// Returns whether or not the deadline is aligned.
+    #[cfg(all(target_os = "linux", feature = "timerfd"))]
+    #[inline]
+    pub(crate) fn realign(&mut self) -> bool {
+        if let DeadlineKind::Repeat(deadline) = &mut self.kind {
+            if let Some(offset) = deadline.aligned {
+                deadline.realign(offset);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Consumes the expiration of the [`Deadline`] noticed at `now` by a waiter other than the
+    /// deadline itself, and schedules the next trigger.
+    ///
+    /// `waited` tells whether the expiration was waited for or if it was already missed.
+    #[inline]
+    pub(crate) fn complete(&mut self, now: Instant, waited: bool) {
+        #[cfg(feature = "stats")]
+        let delivery_time = self.delivery_time();
+
+        if let DeadlineKind::Repeat(deadline) = &mut self.kind {
+            deadline.complete(now, waited)
+        }
+
+        #[cfg(feature = "stats")]
+        self.record(delivery_time, now, !waited);
+    }
+
+    /// Returns the number of ticks skipped the last time the [`Deadline`] expired.
+    ///
+    /// This is always `0` unless the deadline is a repeatable one using
+    /// [`MissedTickBehavior::Skip`].
+    #[inline]
+    pub fn skipped_ticks(&self) -> u64 {
+        match &self.kind {
+            DeadlineKind::Once(_) => 0,
+            DeadlineKind::Repeat(deadline) => deadline.skipped,
+        }
+    }
+}
+
+/* ---------- */
+
+/// The future returned by [`Deadline::wait_async()`].
+#[cfg(feature = "async")]
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct WaitAsync<'a> {
+    /// The deadline to wait for.
+    deadline: &'a mut Deadline,
+
+    /// The last waker registered for the deadline's expiration, along with its registration.
+    registered: Option<(Waker, WakeId)>,
+}
+
+#[cfg(feature = "async")]
+impl Future for WaitAsync<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let now = SystemClock.now();
+        let delivery_time = self.deadline.delivery_time();
+
+        if now >= delivery_time {
+            // The registration, if any, is due and thus about to be removed by the sleeper.
+            let waited = self.registered.take().is_some();
+            self.deadline.complete(now, waited);
+
+            return Poll::Ready(());
+        }
+
+        match &self.registered {
+            Some((waker, _)) if waker.will_wake(cx.waker()) => {}
+            registered => {
+                // The previous waker doesn't need to be woken up anymore.
+                if let Some((_, id)) = registered {
+                    sleeper::cancel(*id);
+                }
+
+                let id = sleeper::wake_at(delivery_time, cx.waker().clone());
+                self.registered = Some((cx.waker().clone(), id));
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+#[cfg(feature = "async")]
+impl Drop for WaitAsync<'_> {
+    fn drop(&mut self) {
+        // Dropped futures don't keep their waker queued until the deadline.
+        if let Some((_, id)) = self.registered.take() {
+            sleeper::cancel(id);
         }
     }
 }
 
 /* ---------- */
 
+/// Defines how a repeatable [`Deadline`] behaves when it expired before being waited on.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MissedTickBehavior {
+    /// Triggers as many times as needed to catch up with the missed ticks.
+    ///
+    /// The deadline won't block until it caught up, which can cause bursts of back-to-back
+    /// wakeups after a long stall.
+    #[default]
+    Burst,
+    /// Skips the missed ticks and triggers on the next multiple of the period.
+    ///
+    /// The number of skipped ticks is reported by [`Deadline::skipped_ticks()`].
+    Skip,
+    /// Reschedules the next tick one period after the time the expiration was noticed.
+    Delay,
+}
+
+/* ---------- */
+
 /// Defines the various kind of deadlines.
 #[derive(Clone, Copy)]
 enum DeadlineKind {
@@ -71,14 +537,14 @@ enum DeadlineKind {
 impl DeadlineKind {
     /// Returns a deadline that can be triggered only once.
     #[inline]
-    fn once(dur: Duration) -> Self {
-        Self::Once(DeadlineOnce::new(dur))
+    fn once(now: Instant, dur: Duration) -> Self {
+        Self::Once(DeadlineOnce::new(now, dur))
     }
 
     /// Returns a deadline that can be triggered repeatedly.
     #[inline]
-    fn repeat(dur: Duration) -> Self {
-        Self::Repeat(DeadlineRepeat::new(dur))
+    fn repeat(now: Instant, dur: Duration, behavior: MissedTickBehavior) -> Self {
+        Self::Repeat(DeadlineRepeat::new(now, dur, behavior))
     }
 }
 
@@ -101,10 +567,10 @@ struct DeadlineOnce {
 }
 
 impl DeadlineOnce {
-    /// Returns a new [`DeadlineOnce`] triggered after `dur` time.
+    /// Returns a new [`DeadlineOnce`] triggered `dur` time after `now`.
     #[inline]
-    fn new(dur: Duration) -> Self {
-        let delivery_time = checked_delivery_time(Instant::now(), dur);
+    fn new(now: Instant, dur: Duration) -> Self {
+        let delivery_time = checked_delivery_time(now, dur);
         Self { delivery_time }
     }
 
@@ -112,22 +578,26 @@ impl DeadlineOnce {
     ///
     /// Once the deadline expires, it always returns true.
     #[inline]
-    fn expired(&self) -> bool {
-        self.remaining_duration() == Duration::ZERO
+    fn expired(&self, clock: &impl Clock) -> bool {
+        self.remaining_duration(clock) == Duration::ZERO
     }
 
     /// Returns the time before the next trigger.
     ///
     /// Once the deadline expires, it always returns [`Duration::ZERO`].
     #[inline]
-    fn remaining_duration(&self) -> Duration {
-        self.delivery_time - Instant::now()
+    fn remaining_duration(&self, clock: &impl Clock) -> Duration {
+        self.delivery_time.saturating_duration_since(clock.now())
     }
 
-    /// Waits until the deadline expires.
+    /// Waits with `wait_until` until the deadline expires.
     #[inline]
-    fn wait(&self) {
-        std::thread::sleep(self.remaining_duration())
+    fn wait<C: Clock>(
+        &self,
+        clock: &C,
+        wait_until: impl FnOnce(&C, Instant) -> Result<(), Cancelled>,
+    ) -> Result<(), Cancelled> {
+        wait_until(clock, self.delivery_time)
     }
 }
 
@@ -140,39 +610,201 @@ struct DeadlineRepeat {
     dur: Duration,
     /// The time when the deadline is triggered.
     delivery_time: Instant,
+    /// What to do when the deadline expired before being waited on.
+    behavior: MissedTickBehavior,
+    /// The number of ticks skipped on the last expiration.
+    skipped: u64,
+    /// The offset of the wall-clock boundaries of an aligned deadline, `None` if not aligned.
+    aligned: Option<Duration>,
 }
 
 impl DeadlineRepeat {
-    /// Returns a new [`DeadlineRepeat`] triggered after `dur` time.
+    /// Returns a new [`DeadlineRepeat`] triggered every `dur` time starting from `now`.
+    #[inline]
+    fn new(now: Instant, dur: Duration, behavior: MissedTickBehavior) -> Self {
+        let delivery_time = checked_delivery_time(now, dur);
+        Self {
+            dur,
+            delivery_time,
+            behavior,
+            skipped: 0,
+            aligned: None,
+        }
+    }
+
+    /// Returns a new [`DeadlineRepeat`] triggered every `dur` time on the grid starting at `start`,
+    /// the first trigger being the first point of the grid not before `now`.
+    #[inline]
+    fn from(start: Instant, now: Instant, dur: Duration, behavior: MissedTickBehavior) -> Self {
+        let delivery_time = if dur.is_zero() {
+            start.max(now)
+        } else {
+            let late = now.saturating_duration_since(start);
+            let ticks = late.as_nanos().div_ceil(dur.as_nanos());
+            let ticks = u64::try_from(ticks).unwrap_or(u64::MAX);
+
+            checked_delivery_time(start, mul_duration(dur, ticks))
+        };
+
+        Self {
+            dur,
+            delivery_time,
+            behavior,
+            skipped: 0,
+            aligned: None,
+        }
+    }
+
+    /// Returns a new [`DeadlineRepeat`] triggered every `dur` time on the wall-clock boundaries
+    /// `UNIX_EPOCH + offset + k * dur`, the first trigger being the first boundary not in the past.
+    #[inline]
+    fn aligned(dur: Duration, offset: Duration) -> Self {
+        let (now, wall) = wall_clock();
+        let period = dur.as_nanos();
+
+        if period == 0 {
+            return Self::from(now, now, dur, MissedTickBehavior::Skip);
+        }
+
+        let offset = offset.as_nanos() % period;
+        let next = wall.saturating_sub(offset).div_ceil(period);
+
+        Self {
+            dur,
+            delivery_time: checked_delivery_time(now, nanos(next * period + offset - wall)),
+            behavior: MissedTickBehavior::Skip,
+            skipped: 0,
+            aligned: Some(nanos(offset)),
+        }
+    }
+
+    /// Schedules the next trigger of an aligned deadline on the wall-clock boundary following
+    /// the current one, or the next boundary not in the past if some were missed.
     #[inline]
-    fn new(dur: Duration) -> Self {
-        let delivery_time = checked_delivery_time(Instant::now(), dur);
-        Self { dur, delivery_time }
+    fn realign(&mut self, offset: Duration) {
+        let (now, wall) = wall_clock();
+        let period = self.dur.as_nanos();
+        let offset = offset.as_nanos();
+
+        // The boundary of the current trigger is the nearest one from the wall-clock time the
+        // trigger was due at, which tolerates the small drifts between the monotonic and the
+        // wall clocks but follows the steps of the wall clock.
+        let late = now.saturating_duration_since(self.delivery_time).as_nanos();
+        let current = (wall.saturating_sub(late).saturating_sub(offset) + period / 2) / period;
+        let last_passed = wall.saturating_sub(offset) / period;
+        let next = current.max(last_passed) + 1;
+
+        self.skipped = u64::try_from(next - current - 1).unwrap_or(u64::MAX);
+        self.delivery_time = checked_delivery_time(now, nanos(next * period + offset - wall));
     }
 
     /// Returns whether or not the deadline expired.
     #[inline]
-    fn expired(&mut self) -> bool {
-        self.remaining_duration() == Duration::ZERO
+    fn expired(&mut self, clock: &impl Clock) -> bool {
+        self.remaining_duration(clock) == Duration::ZERO
     }
 
     /// Returns the time before the next trigger.
     #[inline]
-    fn remaining_duration(&mut self) -> Duration {
-        let ret = self.delivery_time - Instant::now();
+    fn remaining_duration(&mut self, clock: &impl Clock) -> Duration {
+        let now = clock.now();
+        let ret = self.delivery_time.saturating_duration_since(now);
 
         if ret == Duration::ZERO {
-            self.delivery_time += self.dur;
+            self.catch_up(now);
         }
 
         ret
     }
 
-    /// Waits until the deadline expires.
+    /// Waits with `wait_until` until the deadline expires, then schedules the next trigger
+    /// unless the wait failed.
+    #[inline]
+    fn wait<C: Clock>(
+        &mut self,
+        clock: &C,
+        wait_until: impl FnOnce(&C, Instant) -> Result<(), Cancelled>,
+    ) -> Result<(), Cancelled> {
+        let now = clock.now();
+        let waited = now < self.delivery_time;
+
+        if waited {
+            wait_until(clock, self.delivery_time)?;
+        }
+
+        self.complete(now, waited);
+        Ok(())
+    }
+
+    /// Changes the period of the deadline, rescheduling the next trigger one new period
+    /// after the previous one, or on the first point of the new grid not before `now`.
+    #[inline]
+    fn set_period(&mut self, period: Duration, now: Instant) {
+        if let Some(offset) = self.aligned {
+            *self = Self::aligned(period, offset);
+            return;
+        }
+
+        if let Some(prev_delivery_time) = self.delivery_time.checked_sub(self.dur) {
+            let late = now.saturating_duration_since(prev_delivery_time).as_nanos();
+            let periods = late.div_ceil(period.as_nanos().max(1)).max(1);
+            let periods = u64::try_from(periods).unwrap_or(u64::MAX);
+
+            self.delivery_time =
+                checked_delivery_time(prev_delivery_time, mul_duration(period, periods));
+        }
+
+        self.dur = period;
+    }
+
+    /// Schedules the next trigger once the deadline expired, `now` being the time
+    /// the expiration was noticed.
+    ///
+    /// `waited` tells whether the expiration was waited for or if it was already missed.
+    #[inline]
+    fn complete(&mut self, now: Instant, waited: bool) {
+        if let Some(offset) = self.aligned {
+            self.realign(offset);
+        } else if waited {
+            self.skipped = 0;
+            self.delivery_time = checked_delivery_time(self.delivery_time, self.dur);
+        } else {
+            self.catch_up(now);
+        }
+    }
+
+    /// Schedules the next trigger of an expired deadline, `now` being the time
+    /// the expiration was noticed.
     #[inline]
-    fn wait(&mut self) {
-        std::thread::sleep(self.remaining_duration());
-        self.delivery_time += self.dur;
+    fn catch_up(&mut self, now: Instant) {
+        if let Some(offset) = self.aligned {
+            self.realign(offset);
+            return;
+        }
+
+        self.skipped = 0;
+
+        match self.behavior {
+            MissedTickBehavior::Burst => {
+                self.delivery_time = checked_delivery_time(self.delivery_time, self.dur);
+            }
+            MissedTickBehavior::Skip => {
+                let late = now.saturating_duration_since(self.delivery_time);
+                let skipped = late
+                    .as_nanos()
+                    .checked_div(self.dur.as_nanos())
+                    .unwrap_or_default();
+
+                self.skipped = u64::try_from(skipped).unwrap_or(u64::MAX);
+                self.delivery_time = checked_delivery_time(
+                    self.delivery_time,
+                    mul_duration(self.dur, self.skipped.saturating_add(1)),
+                );
+            }
+            MissedTickBehavior::Delay => {
+                self.delivery_time = checked_delivery_time(now, self.dur);
+            }
+        }
     }
 }
 
@@ -183,18 +815,81 @@ impl DeadlineRepeat {
 /// If the given dur is too large, we set the next delivery time to
 /// the next decade.
 #[inline]
-fn checked_delivery_time(instant: Instant, dur: Duration) -> Instant {
+pub(crate) fn checked_delivery_time(instant: Instant, dur: Duration) -> Instant {
     /// Represents a decade.
     const TEN_YEARS: Duration = Duration::from_secs(86400 * 365 * 10);
 
     instant.checked_add(dur).unwrap_or(instant + TEN_YEARS)
 }
 
+/// Returns the current time of both the monotonic clock and the wall clock, the latter in
+/// nanoseconds since the UNIX epoch.
+#[inline]
+fn wall_clock() -> (Instant, u128) {
+    let now = Instant::now();
+    let wall = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    (now, wall.as_nanos())
+}
+
+/// Returns how far the wall-clock time at `at` is from the nearest wall-clock boundary
+/// every `period` shifted by `offset`.
+#[cfg(test)]
+pub(crate) fn boundary_distance(at: Instant, period: Duration, offset: Duration) -> Duration {
+    let (now, wall) = wall_clock();
+    let wall = wall + at.saturating_duration_since(now).as_nanos()
+        - now.saturating_duration_since(at).as_nanos();
+    let phase =
+        (wall + period.as_nanos() - offset.as_nanos() % period.as_nanos()) % period.as_nanos();
+
+    nanos(phase.min(period.as_nanos() - phase))
+}
+
+/// Returns a duration of `nanos` nanoseconds, saturating to [`Duration::MAX`] on overflow.
+#[inline]
+fn nanos(nanos: u128) -> Duration {
+    mul_duration(
+        Duration::from_nanos(1),
+        u64::try_from(nanos).unwrap_or(u64::MAX),
+    )
+}
+
+/// Returns `dur` multiplied by `count`, saturating to [`Duration::MAX`] on overflow.
+#[inline]
+pub(crate) fn mul_duration(dur: Duration, count: u64) -> Duration {
+    /// The number of nanoseconds in a second.
+    const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+    let nanos = dur.as_nanos().saturating_mul(u128::from(count));
+    let subsec_nanos = (nanos % NANOS_PER_SEC) as u32;
+
+    u64::try_from(nanos / NANOS_PER_SEC)
+        .map(|secs| Duration::new(secs, subsec_nanos))
+        .unwrap_or(Duration::MAX)
+}
+
 /* ---------- */
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ManualClock;
+
+    /// Returns a manual clock and a one-time deadline of `dur` relying on it.
+    fn once(dur: Duration) -> (ManualClock, Deadline<ManualClock>) {
+        let clock = ManualClock::new();
+        let deadline = Deadline::once_with_clock(dur, clock.clone());
+        (clock, deadline)
+    }
+
+    /// Returns a manual clock and a repeatable deadline of `dur` relying on it.
+    fn repeat(dur: Duration, behavior: MissedTickBehavior) -> (ManualClock, Deadline<ManualClock>) {
+        let clock = ManualClock::new();
+        let deadline = Deadline::repeat_with_clock(dur, behavior, clock.clone());
+        (clock, deadline)
+    }
 
     #[test]
     fn delivery_time() {
@@ -209,95 +904,395 @@ mod tests {
 
     #[test]
     fn once_expired() {
-        let mut deadline = Deadline::once(Duration::from_millis(100));
+        let (clock, mut deadline) = once(Duration::from_millis(100));
 
         assert!(!deadline.expired());
 
-        std::thread::sleep(Duration::from_millis(110));
+        clock.advance(Duration::from_millis(110));
         assert!(deadline.expired());
         assert!(deadline.expired());
     }
 
     #[test]
     fn once_remains() {
-        let mut deadline = Deadline::once(Duration::from_millis(100));
-        assert!(deadline.remaining_duration() > Duration::ZERO);
-        assert!(deadline.remaining_duration() < Duration::from_millis(100));
+        let (clock, mut deadline) = once(Duration::from_millis(100));
+        assert_eq!(deadline.remaining_duration(), Duration::from_millis(100));
 
-        std::thread::sleep(Duration::from_millis(50));
-        assert!(deadline.remaining_duration() > Duration::ZERO);
-        assert!(deadline.remaining_duration() < Duration::from_millis(50));
+        clock.advance(Duration::from_millis(50));
+        assert_eq!(deadline.remaining_duration(), Duration::from_millis(50));
+        assert_eq!(deadline.remaining_duration(), Duration::from_millis(50));
 
-        std::thread::sleep(Duration::from_millis(51));
-        assert!(deadline.remaining_duration() == Duration::ZERO);
-        assert!(deadline.remaining_duration() == Duration::ZERO);
+        clock.advance(Duration::from_millis(51));
+        assert_eq!(deadline.remaining_duration(), Duration::ZERO);
+        assert_eq!(deadline.remaining_duration(), Duration::ZERO);
     }
 
     #[test]
     fn once_wait() {
-        let mut deadline = Deadline::once(Duration::from_millis(100));
-        let now = Instant::now();
+        let (clock, mut deadline) = once(Duration::from_millis(100));
         deadline.wait();
-        assert!(now.elapsed() >= Duration::from_millis(100));
+        assert_eq!(clock.elapsed(), Duration::from_millis(100));
+
+        let (clock, mut deadline) = once(Duration::from_millis(100));
+        clock.advance(Duration::from_millis(50));
+        deadline.wait();
+        assert_eq!(clock.elapsed(), Duration::from_millis(100));
+
+        deadline.wait();
+        assert_eq!(clock.elapsed(), Duration::from_millis(100));
+    }
 
+    #[test]
+    fn once_wait_system() {
         let mut deadline = Deadline::once(Duration::from_millis(100));
         let now = Instant::now();
         std::thread::sleep(Duration::from_millis(50));
         deadline.wait();
         let delay = now.elapsed();
         assert!(delay >= Duration::from_millis(100));
-        assert!(delay < Duration::from_millis(110));
 
         let now = Instant::now();
         deadline.wait();
         let delay = now.elapsed();
-        assert!(delay < Duration::from_millis(1));
+        assert!(delay < Duration::from_millis(10));
     }
 
     #[test]
     fn repeat_expired() {
-        let mut deadline = Deadline::repeat(Duration::from_millis(100));
+        let (clock, mut deadline) = repeat(Duration::from_millis(100), MissedTickBehavior::Burst);
 
         assert!(!deadline.expired());
 
-        std::thread::sleep(Duration::from_millis(110));
+        clock.advance(Duration::from_millis(110));
         assert!(deadline.expired());
         assert!(!deadline.expired());
     }
 
     #[test]
     fn repeat_remains() {
-        let mut deadline = Deadline::repeat(Duration::from_millis(100));
-        assert!(deadline.remaining_duration() > Duration::ZERO);
-        assert!(deadline.remaining_duration() < Duration::from_millis(100));
+        let (clock, mut deadline) = repeat(Duration::from_millis(100), MissedTickBehavior::Burst);
+        assert_eq!(deadline.remaining_duration(), Duration::from_millis(100));
 
-        std::thread::sleep(Duration::from_millis(50));
-        assert!(deadline.remaining_duration() > Duration::ZERO);
-        assert!(deadline.remaining_duration() < Duration::from_millis(50));
+        clock.advance(Duration::from_millis(50));
+        assert_eq!(deadline.remaining_duration(), Duration::from_millis(50));
 
-        std::thread::sleep(Duration::from_millis(51));
-        assert!(deadline.remaining_duration() == Duration::ZERO);
-        assert!(deadline.remaining_duration() < Duration::from_millis(100));
+        clock.advance(Duration::from_millis(51));
+        assert_eq!(deadline.remaining_duration(), Duration::ZERO);
+        assert_eq!(deadline.remaining_duration(), Duration::from_millis(99));
     }
 
     #[test]
     fn repeat_wait() {
-        let mut deadline = Deadline::repeat(Duration::from_millis(100));
-        let now = Instant::now();
+        let (clock, mut deadline) = repeat(Duration::from_millis(100), MissedTickBehavior::Burst);
         deadline.wait();
-        assert!(now.elapsed() >= Duration::from_millis(100));
+        assert_eq!(clock.elapsed(), Duration::from_millis(100));
+
+        let (clock, mut deadline) = repeat(Duration::from_millis(100), MissedTickBehavior::Burst);
+        clock.advance(Duration::from_millis(50));
+        deadline.wait();
+        assert_eq!(clock.elapsed(), Duration::from_millis(100));
+
+        deadline.wait();
+        assert_eq!(clock.elapsed(), Duration::from_millis(200));
+    }
 
+    #[test]
+    fn repeat_wait_system() {
         let mut deadline = Deadline::repeat(Duration::from_millis(100));
         let now = Instant::now();
-        std::thread::sleep(Duration::from_millis(50));
         deadline.wait();
-        let delay = now.elapsed();
-        assert!(delay >= Duration::from_millis(100));
-        assert!(delay < Duration::from_millis(110));
+        assert!(now.elapsed() >= Duration::from_millis(100));
 
         let now = Instant::now();
         deadline.wait();
         let delay = now.elapsed();
         assert!(delay >= Duration::from_millis(90), "delay = {:?}", delay);
     }
+
+    #[test]
+    fn repeat_burst() {
+        let (clock, mut deadline) = repeat(Duration::from_millis(10), MissedTickBehavior::Burst);
+        clock.advance(Duration::from_millis(55));
+
+        for _ in 0..5 {
+            deadline.wait();
+        }
+        assert_eq!(clock.elapsed(), Duration::from_millis(55));
+        assert_eq!(deadline.skipped_ticks(), 0);
+
+        deadline.wait();
+        assert_eq!(clock.elapsed(), Duration::from_millis(60));
+    }
+
+    #[test]
+    fn repeat_skip() {
+        let (clock, mut deadline) = repeat(Duration::from_millis(100), MissedTickBehavior::Skip);
+        clock.advance(Duration::from_millis(350));
+
+        deadline.wait();
+        assert_eq!(clock.elapsed(), Duration::from_millis(350));
+        assert_eq!(deadline.skipped_ticks(), 2);
+
+        deadline.wait();
+        assert_eq!(clock.elapsed(), Duration::from_millis(400));
+        assert_eq!(deadline.skipped_ticks(), 0);
+    }
+
+    #[test]
+    fn repeat_delay() {
+        let (clock, mut deadline) = repeat(Duration::from_millis(100), MissedTickBehavior::Delay);
+        clock.advance(Duration::from_millis(150));
+
+        assert!(deadline.expired());
+
+        deadline.wait();
+        assert_eq!(clock.elapsed(), Duration::from_millis(250));
+
+        deadline.wait();
+        assert_eq!(clock.elapsed(), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn repeat_set_period() {
+        let (clock, mut deadline) = repeat(Duration::from_millis(100), MissedTickBehavior::Burst);
+        assert_eq!(deadline.period(), Some(Duration::from_millis(100)));
+
+        deadline.wait();
+        assert_eq!(clock.elapsed(), Duration::from_millis(100));
+
+        deadline.set_period(Duration::from_millis(50));
+        assert_eq!(deadline.period(), Some(Duration::from_millis(50)));
+        assert_eq!(deadline.remaining_duration(), Duration::from_millis(50));
+
+        deadline.wait();
+        assert_eq!(clock.elapsed(), Duration::from_millis(150));
+        deadline.wait();
+        assert_eq!(clock.elapsed(), Duration::from_millis(200));
+
+        // Shrinking the period late in a period moves to the new grid without bursting.
+        let (clock, mut deadline) = repeat(Duration::from_secs(1), MissedTickBehavior::Burst);
+        deadline.wait();
+        clock.advance(Duration::from_millis(900));
+        deadline.set_period(Duration::from_millis(200));
+        assert_eq!(deadline.peek_remaining(), Duration::from_millis(100));
+
+        for count in 0..3 {
+            deadline.wait();
+            assert_eq!(clock.elapsed(), Duration::from_millis(2000 + 200 * count));
+        }
+
+        let (_, mut deadline) = once(Duration::from_millis(100));
+        deadline.set_period(Duration::from_millis(50));
+        assert_eq!(deadline.period(), None);
+        assert_eq!(deadline.remaining_duration(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn inspect() {
+        let (clock, mut deadline) = repeat(Duration::from_millis(100), MissedTickBehavior::Skip);
+        let start = clock.now();
+        assert!(!deadline.is_due());
+        assert!(!deadline.consume());
+
+        clock.advance(Duration::from_millis(250));
+
+        // Inspecting the deadline doesn't consume its trigger.
+        for _ in 0..2 {
+            assert!(deadline.is_due());
+            assert_eq!(deadline.peek_remaining(), Duration::ZERO);
+            assert_eq!(deadline.next_delivery(), start + Duration::from_millis(100));
+        }
+
+        assert!(deadline.consume());
+        assert_eq!(deadline.skipped_ticks(), 1);
+        assert_eq!(deadline.next_delivery(), start + Duration::from_millis(300));
+        assert_eq!(deadline.peek_remaining(), Duration::from_millis(50));
+        assert!(!deadline.consume());
+
+        let (clock, mut deadline) = once(Duration::from_millis(100));
+        clock.advance(Duration::from_millis(100));
+        assert!(deadline.consume());
+        assert!(deadline.consume());
+        assert!(deadline.is_due());
+    }
+
+    #[test]
+    fn at() {
+        let clock = ManualClock::new();
+        let start = clock.now();
+
+        let mut deadline =
+            Deadline::at_with_clock(start + Duration::from_millis(100), clock.clone());
+        assert_eq!(deadline.remaining_duration(), Duration::from_millis(100));
+        assert_eq!(deadline.period(), None);
+
+        deadline.wait();
+        assert_eq!(clock.elapsed(), Duration::from_millis(100));
+
+        let mut deadline = Deadline::at_with_clock(start, clock.clone());
+        assert!(deadline.expired());
+
+        let mut deadline = Deadline::at_system_time(SystemTime::now() - Duration::from_secs(1));
+        assert!(deadline.expired());
+
+        let mut deadline = Deadline::at_system_time(SystemTime::now() + Duration::from_secs(1));
+        let remaining = deadline.remaining_duration();
+        assert!(remaining > Duration::from_millis(990) && remaining <= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn repeat_from() {
+        let clock = ManualClock::new();
+        let start = clock.now();
+        clock.advance(Duration::from_millis(250));
+
+        // Deadlines sharing a start are in phase, whenever they were created.
+        let behavior = MissedTickBehavior::Burst;
+        let mut deadline = Deadline::repeat_from_with_clock(
+            start,
+            Duration::from_millis(100),
+            behavior,
+            clock.clone(),
+        );
+        assert_eq!(deadline.remaining_duration(), Duration::from_millis(50));
+
+        deadline.wait();
+        assert_eq!(clock.elapsed(), Duration::from_millis(300));
+        deadline.wait();
+        assert_eq!(clock.elapsed(), Duration::from_millis(400));
+
+        // A point of the grid reached right now is not in the past.
+        let deadline = Deadline::repeat_from_with_clock(
+            start,
+            Duration::from_millis(100),
+            behavior,
+            clock.clone(),
+        );
+        assert_eq!(deadline.delivery_time(), start + Duration::from_millis(400));
+
+        // The first trigger of a start in the future is the start itself.
+        let future = clock.now() + Duration::from_millis(30);
+        let deadline = Deadline::repeat_from_with_clock(
+            future,
+            Duration::from_millis(100),
+            behavior,
+            clock.clone(),
+        );
+        assert_eq!(deadline.delivery_time(), future);
+
+        let deadline =
+            Deadline::repeat_from_with_clock(start, Duration::ZERO, behavior, clock.clone());
+        assert_eq!(deadline.delivery_time(), clock.now());
+    }
+
+    #[test]
+    fn aligned() {
+        let period = Duration::from_millis(100);
+        let offset = Duration::from_millis(30);
+        let mut deadline = Deadline::aligned(period, offset);
+        assert!(deadline.remaining_duration() <= period);
+
+        for _ in 0..3 {
+            deadline.wait();
+
+            // The next trigger is due on a boundary, up to the delay between the reads of
+            // the monotonic and the wall clocks.
+            let distance = boundary_distance(deadline.delivery_time(), period, offset);
+            assert!(
+                distance < Duration::from_millis(5),
+                "distance = {distance:?}"
+            );
+            assert_eq!(deadline.skipped_ticks(), 0);
+        }
+
+        let mut deadline = Deadline::aligned(Duration::ZERO, offset);
+        assert!(deadline.expired());
+    }
+
+    #[test]
+    fn aligned_step() {
+        let period = Duration::from_millis(100);
+        let mut repeat = DeadlineRepeat::aligned(period, Duration::ZERO);
+
+        // The wall clock jumping an hour ahead of the monotonic clock looks like the trigger
+        // was due an hour ago.
+        repeat.delivery_time = Instant::now() - Duration::from_secs(3600);
+        repeat.realign(Duration::ZERO);
+
+        assert!(repeat.skipped >= 35_999, "skipped = {}", repeat.skipped);
+        let remaining = repeat
+            .delivery_time
+            .saturating_duration_since(Instant::now());
+        assert!(remaining <= period);
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn stats() {
+        let (clock, mut deadline) = repeat(Duration::from_millis(100), MissedTickBehavior::Skip);
+
+        deadline.wait();
+        clock.advance(Duration::from_millis(350));
+        deadline.wait();
+        deadline.wait();
+
+        let stats = deadline.stats();
+        assert_eq!(stats.count(), 3);
+        assert_eq!(stats.min(), Duration::ZERO);
+        assert_eq!(stats.max(), Duration::from_millis(250));
+        assert_eq!(stats.missed(), 2);
+        assert_eq!(stats.bursts(), 1);
+
+        deadline.reset_stats();
+        assert_eq!(deadline.stats().count(), 0);
+    }
+
+    #[test]
+    fn wait_strategy() {
+        let now = Instant::now();
+        let mut deadline = Deadline::repeat(Duration::from_millis(20));
+        assert_eq!(deadline.wait_strategy(), WaitStrategy::Sleep);
+
+        deadline.set_wait_strategy(WaitStrategy::Hybrid {
+            margin: Duration::from_millis(2),
+        });
+
+        for count in 1..=5 {
+            deadline.wait();
+
+            let late = now.elapsed() - Duration::from_millis(20 * count);
+            assert!(late < Duration::from_millis(10), "late = {late:?}");
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn wait_async() {
+        use crate::testing::block_on;
+
+        let mut deadline = Deadline::once(Duration::from_millis(100));
+        let now = Instant::now();
+        block_on(deadline.wait_async());
+        assert!(now.elapsed() >= Duration::from_millis(100));
+
+        let now = Instant::now();
+        block_on(deadline.wait_async());
+        assert!(now.elapsed() < Duration::from_millis(10));
+
+        let mut deadline =
+            Deadline::repeat_with(Duration::from_millis(50), MissedTickBehavior::Delay);
+        let now = Instant::now();
+        for count in 1..=3 {
+            block_on(deadline.wait_async());
+            assert!(now.elapsed() >= Duration::from_millis(50 * count));
+        }
+    }
+
+    #[test]
+    fn duration_mul() {
+        let dur = Duration::from_millis(1500);
+        assert_eq!(mul_duration(dur, 0), Duration::ZERO);
+        assert_eq!(mul_duration(dur, 3), Duration::from_millis(4500));
+        assert_eq!(mul_duration(Duration::MAX, 2), Duration::MAX);
+    }
 }
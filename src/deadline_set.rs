@@ -0,0 +1,403 @@
+//! The [`DeadlineSet`] implementation.
+
+use std::sync::Arc;
+use std::task::{Wake, Waker};
+use std::thread::{Thread, ThreadId};
+use std::time::Instant;
+
+use crate::{Clock, Deadline, SystemClock, Watcher};
+
+/* ---------- */
+
+/// A set of [`Deadline`]s and [`Watcher`]s a single thread can wait on at once,
+/// see [`DeadlineSet::wait_any()`].
+///
+/// The entries are identified by their index, in insertion order.
+pub struct DeadlineSet<T = (), C = SystemClock> {
+    /// The deadlines and watchers of the set.
+    entries: Vec<Entry<T, C>>,
+
+    /// The waker unparking the thread waiting on the set, built by the first wait of the thread.
+    ///
+    /// Reusing it lets the watchers recognize it rather than registering it again on each wait.
+    waker: Option<(ThreadId, Waker)>,
+}
+
+impl<T, C> DeadlineSet<T, C> {
+    /// Returns an empty set.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            waker: None,
+        }
+    }
+
+    /// Adds `deadline` to the set and returns its index.
+    #[inline]
+    pub fn push_deadline(&mut self, deadline: Deadline<C>) -> usize {
+        self.entries.push(Entry::Deadline {
+            deadline,
+            done: false,
+            waited: false,
+        });
+        self.entries.len() - 1
+    }
+
+    /// Adds `watcher` to the set and returns its index.
+    #[inline]
+    pub fn push_watcher(&mut self, watcher: Watcher<T>) -> usize {
+        self.entries.push(Entry::Watcher(watcher));
+        self.entries.len() - 1
+    }
+
+    /// Returns the number of deadlines and watchers in the set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether or not the set is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the deadline at `index`, `None` if there is no deadline at this index.
+    #[inline]
+    pub fn deadline_mut(&mut self, index: usize) -> Option<&mut Deadline<C>> {
+        match self.entries.get_mut(index) {
+            Some(Entry::Deadline { deadline, .. }) => Some(deadline),
+            _ => None,
+        }
+    }
+
+    /// Returns the watcher at `index`, `None` if there is no watcher at this index.
+    #[inline]
+    pub fn watcher_mut(&mut self, index: usize) -> Option<&mut Watcher<T>> {
+        match self.entries.get_mut(index) {
+            Some(Entry::Watcher(watcher)) => Some(watcher),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Copy, C: Clock> DeadlineSet<T, C> {
+    /// Blocks the current thread until one of the deadlines expires or one of the watchers is
+    /// notified of a tick, and returns its index.
+    ///
+    /// When several entries are ready, the one that fired first wins, that is the deadline with
+    /// the earliest delivery time or the watcher whose last tick was scheduled first, the lowest
+    /// index breaking the ties. The expiration of the deadline is consumed as [`Deadline::wait()`]
+    /// would, so repeatable deadlines catch up with their missed ticks according to their
+    /// [`MissedTickBehavior`](crate::MissedTickBehavior), in order with the other entries.
+    /// A deadline triggered only once is returned only once. On the other hand, the tick of the
+    /// watcher is left pending so it can be consumed through [`DeadlineSet::watcher_mut()`],
+    /// e.g. with [`Watcher::poll()`].
+    ///
+    /// Returns `None` if nothing can fire anymore, i.e. if the set is empty or only holds expired
+    /// once deadlines and disconnected watchers without pending ticks.
+    ///
+    /// While waiting on watchers, the thread is parked until the earliest deadline in real time,
+    /// regardless of the deadline's [`Clock`].
+    pub fn wait_any(&mut self) -> Option<usize> {
+        let waker = self.waker();
+        let mut parked = false;
+
+        loop {
+            // The earliest ready entry and the earliest deadline to wait for.
+            let mut ready: Option<(Instant, usize)> = None;
+            let mut earliest: Option<(Instant, usize)> = None;
+            let mut watching = false;
+
+            for (index, entry) in self.entries.iter_mut().enumerate() {
+                match entry {
+                    Entry::Deadline { done: true, .. } => {}
+                    Entry::Deadline {
+                        deadline, waited, ..
+                    } => {
+                        let delivery_time = deadline.delivery_time();
+
+                        if delivery_time <= deadline.clock().now() {
+                            // An expiration already due when the wait started is missed.
+                            *waited &= parked;
+                            ready = min(ready, (delivery_time, index));
+                        } else {
+                            *waited = true;
+                            earliest = min(earliest, (delivery_time, index));
+                        }
+                    }
+                    Entry::Watcher(watcher) => {
+                        // Registering the waker first guarantees that a tick occuring right
+                        // after the check unparks the thread.
+                        watcher.register_waker(&waker);
+
+                        if watcher.has_pending() {
+                            let scheduled_at =
+                                watcher.last_scheduled_at().unwrap_or_else(Instant::now);
+                            ready = min(ready, (scheduled_at, index));
+                        } else if !watcher.is_disconnected() {
+                            watching = true;
+                        }
+                    }
+                }
+            }
+
+            if let Some((_, index)) = ready {
+                return Some(self.fire(index));
+            }
+
+            match earliest {
+                // Nothing can fire before the earliest deadline, which is waited for
+                // with its own clock and wait strategy.
+                Some((_, index)) if !watching => return Some(self.fire(index)),
+                Some((delivery_time, _)) => {
+                    std::thread::park_timeout(
+                        delivery_time.saturating_duration_since(Instant::now()),
+                    );
+                }
+                None if watching => std::thread::park(),
+                None => return None,
+            }
+
+            parked = true;
+        }
+    }
+
+    /// Returns the waker unparking the current thread, building it if the set has never been
+    /// waited on by this thread.
+    #[inline]
+    fn waker(&mut self) -> Waker {
+        let thread = std::thread::current();
+
+        match &self.waker {
+            Some((id, waker)) if *id == thread.id() => waker.clone(),
+            _ => {
+                let waker = Waker::from(Arc::new(Unparker(thread.clone())));
+                self.waker = Some((thread.id(), waker.clone()));
+                waker
+            }
+        }
+    }
+
+    /// Consumes the expiration of the entry at `index` if it's a deadline, and returns `index`.
+    ///
+    /// A deadline already due is consumed as waited for if it expired while the set was waiting,
+    /// so it neither drifts nor counts as a burst, and as missed otherwise.
+    #[inline]
+    fn fire(&mut self, index: usize) -> usize {
+        if let Entry::Deadline {
+            deadline,
+            done,
+            waited,
+        } = &mut self.entries[index]
+        {
+            let now = deadline.clock().now();
+
+            if now < deadline.delivery_time() {
+                deadline.wait();
+            } else {
+                deadline.complete(now, *waited);
+            }
+
+            *waited = false;
+            *done = deadline.period().is_none();
+        }
+
+        index
+    }
+}
+
+impl<T, C> Default for DeadlineSet<T, C> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/* ---------- */
+
+/// An entry of a [`DeadlineSet`].
+// The statistics make the deadlines much larger than the watchers, which is fine in a set
+// mostly made of deadlines.
+#[cfg_attr(feature = "stats", allow(clippy::large_enum_variant))]
+enum Entry<T, C> {
+    /// A deadline of the set.
+    Deadline {
+        /// The deadline.
+        deadline: Deadline<C>,
+
+        /// Whether or not the deadline is triggered only once and already expired.
+        done: bool,
+
+        /// Whether or not the deadline was pending at some point of the current wait of the set,
+        /// in which case its expiration is waited for rather than missed.
+        waited: bool,
+    },
+    /// A watcher of the set.
+    Watcher(Watcher<T>),
+}
+
+/* ---------- */
+
+/// Unparks a thread waiting in [`DeadlineSet::wait_any()`] when the timer of a watcher ticks.
+pub(crate) struct Unparker(pub(crate) Thread);
+
+impl Wake for Unparker {
+    #[inline]
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    #[inline]
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/* ---------- */
+
+/// Returns the earliest of `current` and `candidate`, the lowest index breaking the ties.
+#[inline]
+fn min(current: Option<(Instant, usize)>, candidate: (Instant, usize)) -> Option<(Instant, usize)> {
+    Some(current.map_or(candidate, |current| current.min(candidate)))
+}
+
+/* ---------- */
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{ManualClock, MissedTickBehavior, Timer};
+
+    /// Returns a manual clock and a set of repeatable deadlines of 30ms and 50ms relying on it.
+    fn manual_set() -> (ManualClock, DeadlineSet<(), ManualClock>) {
+        let clock = ManualClock::new();
+        let mut set = DeadlineSet::new();
+
+        for millis in [30, 50] {
+            set.push_deadline(Deadline::repeat_with_clock(
+                Duration::from_millis(millis),
+                MissedTickBehavior::Burst,
+                clock.clone(),
+            ));
+        }
+
+        (clock, set)
+    }
+
+    #[test]
+    fn wait_any() {
+        let (clock, mut set) = manual_set();
+
+        for (index, millis) in [(0, 30), (1, 50), (0, 60), (0, 90), (1, 100), (0, 120)] {
+            assert_eq!(set.wait_any(), Some(index));
+            assert_eq!(clock.elapsed(), Duration::from_millis(millis));
+        }
+    }
+
+    #[test]
+    fn burst() {
+        let (clock, mut set) = manual_set();
+        clock.advance(Duration::from_millis(100));
+
+        // The missed ticks are caught up in order, without blocking.
+        for index in [0, 1, 0, 0, 1] {
+            assert_eq!(set.wait_any(), Some(index));
+            assert_eq!(clock.elapsed(), Duration::from_millis(100));
+        }
+
+        assert_eq!(set.wait_any(), Some(0));
+        assert_eq!(clock.elapsed(), Duration::from_millis(120));
+    }
+
+    #[test]
+    fn once() {
+        let mut set = DeadlineSet::<(), ManualClock>::new();
+        assert_eq!(set.wait_any(), None);
+
+        let clock = ManualClock::new();
+        let deadline = Deadline::once_with_clock(Duration::from_millis(10), clock.clone());
+        assert_eq!(set.push_deadline(deadline), 0);
+
+        assert_eq!(set.wait_any(), Some(0));
+        assert_eq!(clock.elapsed(), Duration::from_millis(10));
+        assert_eq!(set.wait_any(), None);
+    }
+
+    #[test]
+    fn watchers() {
+        let mut timer = Timer::new(Duration::from_millis(20));
+        let mut set = DeadlineSet::new();
+        set.push_deadline(Deadline::once(Duration::from_secs(3600)));
+        let index = set.push_watcher(timer.watcher());
+
+        let now = Instant::now();
+        let timer_thread = std::thread::spawn(move || timer.tick());
+
+        assert_eq!(set.wait_any(), Some(index));
+        assert!(now.elapsed() < Duration::from_secs(1));
+
+        // The tick is left pending.
+        assert_eq!(set.wait_any(), Some(index));
+        assert_eq!(set.watcher_mut(index).unwrap().poll().unwrap().seq, 1);
+        assert!(set.deadline_mut(index).is_none());
+
+        timer_thread.join().unwrap();
+
+        // A disconnected watcher without pending ticks can't fire anymore.
+        let mut set = DeadlineSet::<()>::new();
+        set.push_watcher(Timer::new(Duration::from_millis(20)).watcher());
+        assert_eq!(set.wait_any(), None);
+    }
+
+    #[test]
+    fn waker_reuse() {
+        let timer = Timer::new(Duration::from_secs(3600));
+        let mut set = DeadlineSet::new();
+        set.push_deadline(Deadline::repeat(Duration::from_millis(1)));
+        let index = set.push_watcher(timer.watcher());
+
+        // The watcher keeps a single waker however many times the set is waited on between ticks.
+        for _ in 0..200 {
+            assert_eq!(set.wait_any(), Some(0));
+        }
+
+        assert_eq!(set.watcher_mut(index).unwrap().waker_count(), 1);
+    }
+
+    #[test]
+    fn waited() {
+        let period = Duration::from_millis(20);
+        let timer = Timer::new(Duration::from_secs(3600));
+        let mut set = DeadlineSet::new();
+        let deadline = Deadline::repeat_with_clock(period, MissedTickBehavior::Delay, SystemClock);
+        let index = set.push_deadline(deadline);
+        set.push_watcher(timer.watcher());
+        let first = set.deadline_mut(index).unwrap().delivery_time();
+
+        // The expirations the set waits for while watching don't delay the deadline.
+        for count in 1..=3 {
+            assert_eq!(set.wait_any(), Some(index));
+            assert_eq!(
+                set.deadline_mut(index).unwrap().delivery_time(),
+                first + period * count
+            );
+        }
+
+        // An expiration missed before the wait is caught up according to the deadline's behavior.
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(set.wait_any(), Some(index));
+
+        let deadline = set.deadline_mut(index).unwrap();
+        assert!(deadline.delivery_time() >= first + period * 5);
+
+        #[cfg(feature = "stats")]
+        {
+            assert_eq!(deadline.stats().count(), 4);
+            assert_eq!(deadline.stats().bursts(), 1);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! The [`EventFd`] implementation.
+
+use std::io::{Error as IoError, Result as IoResult};
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd};
+
+/* ---------- */
+
+/// A Linux `eventfd` signaling the readiness of a [`Watcher`](crate::Watcher) to poll-based loops.
+#[derive(Debug)]
+pub(crate) struct EventFd {
+    /// The `eventfd` file descriptor.
+    fd: OwnedFd,
+}
+
+impl EventFd {
+    /// Returns a new non-blocking eventfd.
+    pub(crate) fn new() -> IoResult<Self> {
+        // SAFETY: the flags are valid and the returned descriptor is checked right away.
+        let fd = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK | libc::EFD_CLOEXEC) };
+
+        if fd < 0 {
+            return Err(IoError::last_os_error());
+        }
+
+        // SAFETY: `fd` is a freshly created descriptor owned by nobody else.
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        Ok(Self { fd })
+    }
+
+    /// Makes the eventfd readable until it is read.
+    #[inline]
+    pub(crate) fn signal(&self) {
+        let one = 1u64;
+
+        // SAFETY: the buffer is valid for 8 bytes, the size of the eventfd counter. The write
+        // only fails if the counter is about to overflow, in which case the eventfd is readable
+        // anyway.
+        unsafe {
+            libc::write(
+                self.fd.as_raw_fd(),
+                (&one as *const u64).cast(),
+                size_of::<u64>(),
+            )
+        };
+    }
+
+    /// Clears the readiness of the eventfd.
+    #[inline]
+    pub(crate) fn clear(&self) {
+        let mut counter = 0u64;
+
+        // SAFETY: the buffer is valid for 8 bytes, the size of the eventfd counter. The read
+        // only fails if the eventfd isn't readable, in which case it is already cleared.
+        unsafe {
+            libc::read(
+                self.fd.as_raw_fd(),
+                (&mut counter as *mut u64).cast(),
+                size_of::<u64>(),
+            )
+        };
+    }
+}
+
+impl AsFd for EventFd {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
@@ -14,6 +14,20 @@
 //! * [`Deadline::repeat()`] returns a [`Deadline`] that can be triggered multiple times. In this case, if too much
 //!   time have passed between two [`Deadline::wait()`] calls, it will try to catch up.
 //!
+//! [`Deadline::repeat_with()`] lets users choose how a repeatable deadline catches up with missed ticks through a
+//! [`MissedTickBehavior`]: bursting (the default), skipping them or delaying the next ones.
+//!
+//! [`Deadline::at()`], [`Deadline::at_system_time()`] and [`Deadline::repeat_from()`] start from an absolute point in time
+//! rather than from the deadline's creation, so threads or processes sharing a start epoch can create deadlines with identical phase.
+//! [`Deadline::aligned()`] and [`Timer::aligned()`] trigger on wall-clock boundaries instead, e.g. on every whole second,
+//! and re-align when the wall clock is stepped.
+//!
+//! [`Deadline::wait_cancellable()`] waits until the deadline expires unless another thread cancels a [`CancelToken`]
+//! first, e.g. during an application's shutdown.
+//!
+//! A [`DeadlineSet`] holds several deadlines and watchers, and its [`DeadlineSet::wait_any()`] sleeps until the earliest
+//! one fires and returns its index.
+//!
 //! ## Examples
 //!
 //! ### Basic example
@@ -97,12 +111,23 @@
 //!
 //! Usually, the timer runs in a loop in its own thread, while the [`Watcher`]s are passed in another threads.
 //! The timer ticks periodically and notifies one or more watchers.
+//! [`Timer::spawn()`] takes care of running the timer in its own thread and returns a [`TimerHandle`] that
+//! stops the timer once dropped, while a [`TimerBuilder`] allows configuring the thread.
+//! Subsystems running at a lower rate can use watchers notified every n-th tick only,
+//! see [`Timer::watcher_every()`], so they stay phase-locked to the same timer.
+//! Watchers receive a [`TickInfo`] with the time each tick was scheduled and fired at.
+//! A timer can also broadcast a small payload with each tick, see [`Timer::with_payload()`]
+//! and [`Timer::tick_with()`], which watchers get back from [`Watcher::has_ticked_with()`].
+//! In barrier mode, the timer waits for every watcher to acknowledge a tick before moving on,
+//! see [`Timer::set_barrier()`].
+//!
+//! Both deadlines and timers put the thread to sleep by default. When more precision is required,
+//! [`Deadline::set_wait_strategy()`] and [`Timer::set_wait_strategy()`] allow spinning instead, either
+//! entirely or only for a short margin before the delivery time, see [`WaitStrategy`].
 //!
 //! ## Example
 //!
 //! ```
-//! use std::sync::atomic::{AtomicBool, Ordering};
-//! use std::sync::Arc;
 //! use std::time::{Duration, Instant};
 //! # use minuteurs::Timer;
 //!
@@ -115,27 +140,21 @@
 //! let mut watcher2 = watcher1.clone();
 //!
 //! let now = Instant::now();
-//! let stop = Arc::new(AtomicBool::default());
 //!
 //! // Spawn two threads.
 //! // They should prints approximatively every 1s.
-//! let stop_clone = Arc::clone(&stop);
 //! let thread1 = std::thread::spawn(move || {
-//!     while !stop_clone.load(Ordering::SeqCst) {
-//!         if watcher1.has_ticked() {
-//!             let elapsed = now.elapsed();
-//!             println!("thread1 ticked at {elapsed:?}",)
-//!         }
+//!     // Parks the thread until the timer ticks, and stops once the timer is dropped.
+//!     while watcher1.wait().is_ok() {
+//!         let elapsed = now.elapsed();
+//!         println!("thread1 ticked at {elapsed:?}",)
 //!     }
 //! });
 //!
-//! let stop_clone = Arc::clone(&stop);
 //! let thread2 = std::thread::spawn(move || {
-//!     while !stop_clone.load(Ordering::SeqCst) {
-//!         if watcher2.has_ticked() {
-//!             let elapsed = now.elapsed();
-//!             println!("thread2 ticked at {elapsed:?}",)
-//!         }
+//!     while watcher2.wait().is_ok() {
+//!         let elapsed = now.elapsed();
+//!         println!("thread2 ticked at {elapsed:?}",)
 //!     }
 //! });
 //!
@@ -143,7 +162,8 @@
 //!     timer.tick();
 //! }
 //!
-//! stop.store(true, Ordering::SeqCst);
+//! // Dropping the timer disconnects the watchers.
+//! drop(timer);
 //!
 //! // Obligatory clean up.
 //! let _ = thread1.join();
@@ -163,9 +183,66 @@
 //! thread2 ticked at 5.000874695s
 //! thread1 ticked at 5.000875316s
 //! ```
+//!
+//! # Clocks
+//!
+//! Both [`Deadline`]s and [`Timer`]s measure time through a [`Clock`]. They use the [`SystemClock`] by default,
+//! but [`Deadline::once_with_clock()`], [`Deadline::repeat_with_clock()`] and [`Timer::with_clock()`] accept any
+//! other implementation.
+//!
+//! The [`ManualClock`] only moves forward when told to, which allows testing timing logic instantly and
+//! deterministically:
+//!
+//! ```
+//! use std::time::Duration;
+//! # use minuteurs::{Deadline, ManualClock};
+//!
+//! let clock = ManualClock::new();
+//! let mut deadline = Deadline::once_with_clock(Duration::from_secs(3600), clock.clone());
+//! assert!(!deadline.expired());
+//!
+//! clock.advance(Duration::from_secs(3600));
+//! assert!(deadline.expired());
+//! ```
+//!
+//! # Features
+//!
+//! * `abstime` (Linux only): provides the `MonotonicClock`, a [`Clock`] sleeping until absolute deadlines
+//!   with `clock_nanosleep(CLOCK_MONOTONIC, TIMER_ABSTIME)` so periodic deadlines and timers don't drift.
+//! * `async`: provides `Deadline::wait_async()` and implements `futures_core::Stream` for [`Watcher`]s so
+//!   they can be awaited. This doesn't depend on any specific async runtime.
+//! * `eventfd` (Linux only): provides `Watcher::as_event_fd()`, an `eventfd` signaled on each tick of the watcher
+//!   so it can be multiplexed with sockets in a `poll`/`epoll` loop, and `Watcher::read_event_fd()` to consume its ticks.
+//! * `stats`: records how late deadlines and timers wake up, see `Deadline::stats()` and `Timer::stats()`.
+//! * `timerfd` (Linux only): provides the `TimerFd`, a periodic `timerfd` that can be registered in an event loop such
+//!   as epoll or mio rather than blocking a thread per timer, see `Timer::timerfd()` and `Timer::tick_fd()`.
 
+mod cancel;
+mod clock;
 mod deadline;
+mod deadline_set;
+#[cfg(all(target_os = "linux", feature = "eventfd"))]
+mod eventfd;
+mod seqlock;
+#[cfg(feature = "async")]
+mod sleeper;
+#[cfg(feature = "stats")]
+mod stats;
+mod sync;
+#[cfg(all(test, feature = "async"))]
+mod testing;
 mod timer;
+#[cfg(all(target_os = "linux", feature = "timerfd"))]
+mod timerfd;
+mod wait;
 
+pub use cancel::*;
+pub use clock::*;
 pub use deadline::*;
+pub use deadline_set::*;
+#[cfg(feature = "stats")]
+pub use stats::*;
 pub use timer::*;
+#[cfg(all(target_os = "linux", feature = "timerfd"))]
+pub use timerfd::*;
+pub use wait::*;
@@ -0,0 +1,191 @@
+//! The [`SeqLock`] implementation.
+//!
+//! Loom can't model the racy reads of a seqlock, so the lock is backed by a mutex
+//! with the `loom` cfg.
+
+#[cfg(not(loom))]
+use std::cell::UnsafeCell;
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+#[cfg(not(loom))]
+use std::sync::atomic::{fence, AtomicU64, Ordering};
+#[cfg(loom)]
+use std::sync::PoisonError;
+
+#[cfg(loom)]
+use crate::sync::Mutex;
+
+/* ---------- */
+
+/// A single-writer lock allowing readers to copy a value without ever blocking the writer.
+///
+/// The writer bumps a version counter before and after each write, readers retry
+/// until they read the value without any write in between.
+#[cfg(not(loom))]
+pub(crate) struct SeqLock<T> {
+    /// The version of the value, odd while a write is in progress.
+    version: AtomicU64,
+
+    /// The protected value.
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: readers only ever copy the value out, and torn reads are discarded thanks to the version.
+#[cfg(not(loom))]
+unsafe impl<T: Copy + Send> Sync for SeqLock<T> {}
+
+#[cfg(not(loom))]
+impl<T: Copy> SeqLock<T> {
+    /// Returns a new lock protecting `value`.
+    #[inline]
+    pub(crate) fn new(value: T) -> Self {
+        Self {
+            version: AtomicU64::new(0),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Returns a copy of the protected value.
+    #[inline]
+    pub(crate) fn read(&self) -> T {
+        loop {
+            let version = self.version.load(Ordering::Acquire);
+
+            if version & 1 == 0 {
+                // SAFETY: the pointer is valid and the value is discarded if a write happened
+                // during the read. The volatile read prevents the compiler from assuming the
+                // value can't change under its feet.
+                let value = unsafe { self.value.get().read_volatile() };
+                fence(Ordering::Acquire);
+
+                if self.version.load(Ordering::Relaxed) == version {
+                    return value;
+                }
+            }
+
+            std::hint::spin_loop();
+        }
+    }
+
+    /// Replaces the protected value by `value`.
+    ///
+    /// # Safety
+    ///
+    /// There must be a single writer at a time.
+    #[inline]
+    pub(crate) unsafe fn write(&self, value: T) {
+        let version = self.version.load(Ordering::Relaxed);
+
+        self.version
+            .store(version.wrapping_add(1), Ordering::Relaxed);
+        fence(Ordering::Release);
+
+        // SAFETY: the pointer is valid, the caller guarantees there is no concurrent write
+        // and concurrent reads are discarded by the readers since the version is odd.
+        unsafe { self.value.get().write_volatile(value) };
+
+        self.version
+            .store(version.wrapping_add(2), Ordering::Release);
+    }
+}
+
+#[cfg(not(loom))]
+impl<T> Debug for SeqLock<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("SeqLock")
+            .field("version", &self.version)
+            .finish_non_exhaustive()
+    }
+}
+
+/* ---------- */
+
+/// The mutex-backed stand-in of the seqlock used for model checking.
+#[cfg(loom)]
+pub(crate) struct SeqLock<T> {
+    /// The protected value.
+    value: Mutex<T>,
+}
+
+#[cfg(loom)]
+impl<T: Copy> SeqLock<T> {
+    /// Returns a new lock protecting `value`.
+    #[inline]
+    pub(crate) fn new(value: T) -> Self {
+        Self {
+            value: Mutex::new(value),
+        }
+    }
+
+    /// Returns a copy of the protected value.
+    #[inline]
+    pub(crate) fn read(&self) -> T {
+        *self.value.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Replaces the protected value by `value`.
+    ///
+    /// # Safety
+    ///
+    /// There must be a single writer at a time.
+    #[inline]
+    pub(crate) unsafe fn write(&self, value: T) {
+        *self.value.lock().unwrap_or_else(PoisonError::into_inner) = value;
+    }
+}
+
+#[cfg(loom)]
+impl<T> Debug for SeqLock<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("SeqLock").finish_non_exhaustive()
+    }
+}
+
+/* ---------- */
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn read_write() {
+        let lock = SeqLock::new(1);
+        assert_eq!(lock.read(), 1);
+
+        // SAFETY: single writer.
+        unsafe { lock.write(2) };
+        assert_eq!(lock.read(), 2);
+    }
+
+    #[test]
+    fn no_tearing() {
+        let lock = Arc::new(SeqLock::new([0u64; 16]));
+        let stop = Arc::new(AtomicBool::default());
+
+        let readers: Vec<_> = (0..2)
+            .map(|_| {
+                let lock = Arc::clone(&lock);
+                let stop = Arc::clone(&stop);
+
+                std::thread::spawn(move || {
+                    while !stop.load(Ordering::Acquire) {
+                        let value = lock.read();
+                        assert!(value.iter().all(|&v| v == value[0]), "torn read");
+                    }
+                })
+            })
+            .collect();
+
+        for i in 0..100_000 {
+            // SAFETY: single writer.
+            unsafe { lock.write([i; 16]) };
+        }
+
+        stop.store(true, Ordering::Release);
+        for reader in readers {
+            reader.join().unwrap();
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! A background thread waking up [`Waker`]s at given points in time.
+//!
+//! This allows deadlines to be awaited without depending on any specific async runtime.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::sync::{Condvar, Mutex, MutexGuard, OnceLock, PoisonError};
+use std::task::Waker;
+use std::time::Instant;
+
+/* ---------- */
+
+/// Wakes `waker` up once `at` is reached.
+///
+/// Returns the identifier of the registration, see [`cancel()`].
+pub(crate) fn wake_at(at: Instant, waker: Waker) -> WakeId {
+    sleeper().register(at, waker)
+}
+
+/// Cancels the registration `id` if its waker hasn't been woken up yet.
+pub(crate) fn cancel(id: WakeId) {
+    sleeper().cancel(id);
+}
+
+/// Returns the sleeper shared by the whole process, spawning its thread on the first call.
+fn sleeper() -> &'static Sleeper {
+    /// The sleeper shared by the whole process.
+    static SLEEPER: OnceLock<&'static Sleeper> = OnceLock::new();
+
+    SLEEPER.get_or_init(|| {
+        let sleeper: &'static Sleeper = Box::leak(Box::default());
+
+        std::thread::Builder::new()
+            .name("minuteurs-sleeper".into())
+            .spawn(move || sleeper.run())
+            .expect("failed to spawn the sleeper thread");
+
+        sleeper
+    })
+}
+
+/// The identifier of a waker registered in the [`Sleeper`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct WakeId(u64);
+
+/* ---------- */
+
+/// Holds the wakers waiting for a point in time.
+#[derive(Debug, Default)]
+struct Sleeper {
+    /// The registered wakers, the earliest one first.
+    queue: Mutex<BinaryHeap<Entry>>,
+
+    /// Notifies the sleeper thread of new registrations.
+    cond: Condvar,
+
+    /// The identifier of the next registration.
+    next_id: AtomicU64,
+}
+
+impl Sleeper {
+    /// Registers `waker` to be woken up once `at` is reached.
+    fn register(&self, at: Instant, waker: Waker) -> WakeId {
+        let id = WakeId(self.next_id.fetch_add(1, AtomicOrdering::Relaxed));
+        let mut queue = self.queue();
+        let earliest = queue.peek().is_none_or(|entry| at < entry.at);
+
+        queue.push(Entry { at, id, waker });
+
+        // The sleeper thread only needs to recompute its sleep time if the new entry
+        // is the next one to wake up.
+        if earliest {
+            self.cond.notify_one();
+        }
+
+        id
+    }
+
+    /// Removes the registration `id` from the queue.
+    fn cancel(&self, id: WakeId) {
+        self.queue().retain(|entry| entry.id != id);
+    }
+
+    /// Locks the queue of the registered wakers.
+    #[inline]
+    fn queue(&self) -> MutexGuard<'_, BinaryHeap<Entry>> {
+        self.queue.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Runs the sleeper loop, waking the wakers up as their time comes.
+    fn run(&self) {
+        let mut queue = self.queue();
+        let mut expired = Vec::new();
+
+        loop {
+            let now = Instant::now();
+
+            while queue.peek().is_some_and(|entry| entry.at <= now) {
+                expired.extend(queue.pop());
+            }
+
+            // The wakers are woken up without the lock, since they might register
+            // themselves again.
+            if !expired.is_empty() {
+                drop(queue);
+                expired.drain(..).for_each(|entry| entry.waker.wake());
+                queue = self.queue();
+                continue;
+            }
+
+            queue = match queue.peek() {
+                Some(entry) => {
+                    let timeout = entry.at - now;
+                    self.cond
+                        .wait_timeout(queue, timeout)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+                None => self
+                    .cond
+                    .wait(queue)
+                    .unwrap_or_else(PoisonError::into_inner),
+            };
+        }
+    }
+}
+
+/* ---------- */
+
+/// A waker registered in the [`Sleeper`].
+#[derive(Debug)]
+struct Entry {
+    /// The time the waker must be woken up at.
+    at: Instant,
+
+    /// The identifier of the registration.
+    id: WakeId,
+
+    /// The waker to wake up.
+    waker: Waker,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.at == other.at
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so the binary heap pops the earliest entry first.
+        other.at.cmp(&self.at)
+    }
+}
+
+/* ---------- */
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::Wake;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::testing::Counter;
+
+    #[test]
+    fn wake_order() {
+        let first = Arc::new(Counter::default());
+        let second = Arc::new(Counter::default());
+        let now = Instant::now();
+
+        wake_at(
+            now + Duration::from_millis(100),
+            Waker::from(Arc::clone(&second)),
+        );
+        wake_at(
+            now + Duration::from_millis(50),
+            Waker::from(Arc::clone(&first)),
+        );
+
+        std::thread::sleep(Duration::from_millis(75));
+        assert_eq!(first.count(), 1);
+        assert_eq!(second.count(), 0);
+
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(first.count(), 1);
+        assert_eq!(second.count(), 1);
+    }
+
+    #[test]
+    fn cancel() {
+        let counter = Arc::new(Counter::default());
+        let id = wake_at(
+            Instant::now() + Duration::from_millis(20),
+            Waker::from(Arc::clone(&counter)),
+        );
+
+        super::cancel(id);
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(counter.count(), 0);
+        assert_eq!(Arc::strong_count(&counter), 1, "the waker is still queued");
+    }
+
+    #[test]
+    fn reentrant() {
+        /// Registers itself again when woken up for the first time.
+        #[derive(Default)]
+        struct Rearm(AtomicUsize);
+
+        impl Wake for Rearm {
+            fn wake(self: Arc<Self>) {
+                if self.0.fetch_add(1, Ordering::SeqCst) == 0 {
+                    wake_at(Instant::now(), Waker::from(self));
+                }
+            }
+        }
+
+        let rearm = Arc::new(Rearm::default());
+        wake_at(Instant::now(), Waker::from(Arc::clone(&rearm)));
+
+        // The sleeper must not deadlock on the registration made while waking up.
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(rearm.0.load(Ordering::SeqCst), 2);
+
+        let counter = Arc::new(Counter::default());
+        wake_at(Instant::now(), Waker::from(Arc::clone(&counter)));
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(counter.count(), 1);
+    }
+}
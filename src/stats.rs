@@ -0,0 +1,329 @@
+//! The [`Stats`] implementation.
+
+use std::time::Duration;
+
+/* ---------- */
+
+/// The number of bits of a lateness, below its most significant one, that select its
+/// sub-bucket within a power of two.
+const SUB_BITS: u32 = 4;
+
+/// The number of linear sub-buckets per power of two of the lateness histogram.
+const SUB_BUCKETS: usize = 1 << SUB_BITS;
+
+/// The number of buckets of the lateness histogram: the latenesses below [`SUB_BUCKETS`]
+/// nanoseconds get a bucket each, then each power of two up to `2^63` gets [`SUB_BUCKETS`].
+const BUCKETS: usize = (64 - SUB_BITS as usize + 1) * SUB_BUCKETS;
+
+/// Statistics about how late a [`Deadline`](crate::Deadline) or a [`Timer`](crate::Timer)
+/// woke up, see [`Deadline::stats()`](crate::Deadline::stats) and
+/// [`Timer::stats()`](crate::Timer::stats).
+///
+/// The lateness of a wake is the time elapsed between the delivery time and the time the
+/// thread actually woke up. Besides the usual min/max/mean/standard deviation, the latenesses are
+/// recorded in a log-linear histogram: each power of two of nanoseconds is split into 16 buckets
+/// of equal width, so a bucket is never wider than a 16th of the latenesses it counts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    /// The number of recorded wakes.
+    count: u64,
+
+    /// The smallest lateness, in nanoseconds.
+    min: u64,
+
+    /// The largest lateness, in nanoseconds.
+    max: u64,
+
+    /// The mean of the latenesses, in nanoseconds.
+    mean: f64,
+
+    /// The sum of the squared differences from the mean, in nanoseconds².
+    m2: f64,
+
+    /// The number of ticks skipped to catch up with a late deadline.
+    missed: u64,
+
+    /// The number of wakes that occured back-to-back because the deadline already expired.
+    bursts: u64,
+
+    /// The histogram of the latenesses.
+    histogram: [u64; BUCKETS],
+}
+
+impl Stats {
+    /// Returns empty statistics.
+    #[inline]
+    pub(crate) const fn new() -> Self {
+        Self {
+            count: 0,
+            min: u64::MAX,
+            max: 0,
+            mean: 0.0,
+            m2: 0.0,
+            missed: 0,
+            bursts: 0,
+            histogram: [0; BUCKETS],
+        }
+    }
+
+    /// Records a wake that occured `late` after the delivery time.
+    ///
+    /// `burst` tells whether the deadline already expired before the wait and `missed`
+    /// is the number of ticks skipped to catch up.
+    pub(crate) fn record(&mut self, late: Duration, burst: bool, missed: u64) {
+        let nanos = u64::try_from(late.as_nanos()).unwrap_or(u64::MAX);
+
+        self.count += 1;
+        self.min = self.min.min(nanos);
+        self.max = self.max.max(nanos);
+
+        // Welford's online algorithm keeps the variance numerically stable.
+        let delta = nanos as f64 - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (nanos as f64 - self.mean);
+
+        self.histogram[bucket(nanos)] += 1;
+        self.missed = self.missed.saturating_add(missed);
+        self.bursts += u64::from(burst);
+    }
+
+    /// Clears every recorded wake.
+    #[inline]
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Returns the number of recorded wakes.
+    #[inline]
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the smallest lateness, or [`Duration::ZERO`] if no wake was recorded.
+    #[inline]
+    pub fn min(&self) -> Duration {
+        match self.count {
+            0 => Duration::ZERO,
+            _ => Duration::from_nanos(self.min),
+        }
+    }
+
+    /// Returns the largest lateness.
+    #[inline]
+    pub fn max(&self) -> Duration {
+        Duration::from_nanos(self.max)
+    }
+
+    /// Returns the mean lateness.
+    #[inline]
+    pub fn mean(&self) -> Duration {
+        Duration::from_nanos(self.mean.round() as u64)
+    }
+
+    /// Returns the standard deviation of the latenesses.
+    #[inline]
+    pub fn stddev(&self) -> Duration {
+        match self.count {
+            0 => Duration::ZERO,
+            count => Duration::from_nanos((self.m2 / count as f64).sqrt().round() as u64),
+        }
+    }
+
+    /// Returns the total number of ticks skipped to catch up with a late deadline.
+    ///
+    /// Only the deadlines using [`MissedTickBehavior::Skip`](crate::MissedTickBehavior::Skip)
+    /// skip ticks.
+    #[inline]
+    pub fn missed(&self) -> u64 {
+        self.missed
+    }
+
+    /// Returns the number of wakes that occured back-to-back because the deadline
+    /// already expired before the wait.
+    #[inline]
+    pub fn bursts(&self) -> u64 {
+        self.bursts
+    }
+
+    /// Returns the non-empty buckets of the lateness histogram, as the lower bound
+    /// of the bucket along with its number of wakes.
+    pub fn histogram(&self) -> impl Iterator<Item = (Duration, u64)> + '_ {
+        self.histogram
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(i, &count)| (lower_bound(i), count))
+    }
+
+    /// Returns an upper bound of the lateness under which `quantile` of the wakes occured,
+    /// `quantile` being between `0.0` and `1.0`.
+    ///
+    /// The result is as precise as the histogram, which overestimates the lateness
+    /// by at most a 16th.
+    pub fn quantile(&self, quantile: f64) -> Duration {
+        let target = (quantile.clamp(0.0, 1.0) * self.count as f64).ceil() as u64;
+
+        if target == 0 {
+            return self.min();
+        }
+
+        let mut seen = 0;
+
+        for (i, &count) in self.histogram.iter().enumerate() {
+            seen += count;
+
+            if count > 0 && seen >= target {
+                return lower_bound(i + 1).min(self.max()).max(self.min());
+            }
+        }
+
+        Duration::ZERO
+    }
+}
+
+impl Default for Stats {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/* ---------- */
+
+/// Returns the index of the histogram's bucket counting a lateness of `nanos` nanoseconds.
+#[inline]
+fn bucket(nanos: u64) -> usize {
+    if nanos < SUB_BUCKETS as u64 {
+        return nanos as usize;
+    }
+
+    // The most significant bit selects the power of two and the next `SUB_BITS` bits
+    // select the sub-bucket.
+    let shift = nanos.ilog2() - SUB_BITS;
+    let sub = (nanos >> shift) as usize - SUB_BUCKETS;
+
+    (shift as usize + 1) * SUB_BUCKETS + sub
+}
+
+/// Returns the smallest lateness counted by the histogram's bucket `i`, saturating
+/// past the last bucket.
+#[inline]
+fn lower_bound(i: usize) -> Duration {
+    if i < SUB_BUCKETS {
+        return Duration::from_nanos(i as u64);
+    }
+
+    let shift = (i / SUB_BUCKETS - 1) as u32;
+    let nanos = ((SUB_BUCKETS + i % SUB_BUCKETS) as u128) << shift;
+
+    Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+}
+
+/* ---------- */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let stats = Stats::new();
+
+        assert_eq!(stats.count(), 0);
+        assert_eq!(stats.min(), Duration::ZERO);
+        assert_eq!(stats.max(), Duration::ZERO);
+        assert_eq!(stats.mean(), Duration::ZERO);
+        assert_eq!(stats.stddev(), Duration::ZERO);
+        assert_eq!(stats.quantile(0.5), Duration::ZERO);
+        assert_eq!(stats.histogram().count(), 0);
+    }
+
+    #[test]
+    fn record() {
+        let mut stats = Stats::new();
+
+        for micros in [10, 20, 30, 40] {
+            stats.record(Duration::from_micros(micros), false, 0);
+        }
+        stats.record(Duration::from_micros(100), true, 3);
+
+        assert_eq!(stats.count(), 5);
+        assert_eq!(stats.min(), Duration::from_micros(10));
+        assert_eq!(stats.max(), Duration::from_micros(100));
+        assert_eq!(stats.mean(), Duration::from_micros(40));
+        assert_eq!(stats.stddev().as_micros(), 31);
+        assert_eq!(stats.missed(), 3);
+        assert_eq!(stats.bursts(), 1);
+
+        stats.reset();
+        assert_eq!(stats, Stats::new());
+    }
+
+    #[test]
+    fn histogram() {
+        let mut stats = Stats::new();
+
+        stats.record(Duration::ZERO, false, 0);
+        stats.record(Duration::from_nanos(1), false, 0);
+        stats.record(Duration::from_nanos(1000), false, 0);
+        stats.record(Duration::from_nanos(1023), false, 0);
+        stats.record(Duration::from_nanos(1024), false, 0);
+        stats.record(Duration::MAX, false, 0);
+
+        let histogram: Vec<_> = stats.histogram().collect();
+        assert_eq!(
+            histogram,
+            [
+                (Duration::ZERO, 1),
+                (Duration::from_nanos(1), 1),
+                (Duration::from_nanos(992), 2),
+                (Duration::from_nanos(1024), 1),
+                (Duration::from_nanos(31 << 59), 1),
+            ]
+        );
+
+        assert_eq!(stats.quantile(0.0), Duration::ZERO);
+        assert_eq!(stats.quantile(0.5), Duration::from_nanos(1024));
+        assert_eq!(stats.quantile(0.8), Duration::from_nanos(1088));
+        assert_eq!(stats.quantile(1.0), Duration::from_nanos(u64::MAX));
+    }
+
+    #[test]
+    fn buckets() {
+        for i in 0..BUCKETS {
+            let lower = lower_bound(i).as_nanos() as u64;
+            let upper = lower_bound(i + 1).as_nanos() as u64;
+
+            assert_eq!(bucket(lower), i);
+            assert_eq!(bucket(upper - 1), i);
+            assert!(upper - lower <= lower / SUB_BUCKETS as u64 + 1);
+        }
+
+        assert_eq!(bucket(u64::MAX), BUCKETS - 1);
+    }
+
+    #[test]
+    fn quantile_precision() {
+        let mut stats = Stats::new();
+        let mut latenesses: Vec<_> = (1..=1000u64)
+            .map(|i| Duration::from_nanos(i * i * 7919 % 50_000_000))
+            .collect();
+
+        for &late in &latenesses {
+            stats.record(late, false, 0);
+        }
+
+        latenesses.sort();
+
+        for quantile in [0.01, 0.1, 0.25, 0.5, 0.75, 0.9, 0.99, 0.999] {
+            let exact = latenesses[(quantile * 1000.0_f64).ceil() as usize - 1];
+            let estimate = stats.quantile(quantile);
+
+            assert!(estimate >= exact, "{quantile}: {estimate:?} < {exact:?}");
+            assert!(
+                estimate <= exact + exact / 16,
+                "{quantile}: {estimate:?} > {exact:?} + 1/16"
+            );
+        }
+    }
+}
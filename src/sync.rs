@@ -0,0 +1,55 @@
+//! The synchronization primitives shared by the [`Timer`](crate::Timer)s and their
+//! [`Watcher`](crate::Watcher)s.
+//!
+//! With the `loom` cfg, they are swapped for [loom](https://docs.rs/loom)'s ones so the
+//! synchronization can be model checked. This is a cfg rather than a feature so it can never be
+//! enabled by accident, e.g. by `--all-features` or a dependent crate. The model checks only run
+//! within `loom::model`, so the other tests must be filtered out:
+//! `RUSTFLAGS="--cfg loom" cargo test --release loom`.
+
+use std::sync::PoisonError;
+use std::time::{Duration, Instant};
+
+#[cfg(loom)]
+pub(crate) use loom::sync::atomic::{fence, AtomicBool, AtomicU64, AtomicUsize};
+#[cfg(loom)]
+pub(crate) use loom::sync::{Condvar, Mutex, MutexGuard};
+#[cfg(not(loom))]
+pub(crate) use std::sync::atomic::{fence, AtomicBool, AtomicU64, AtomicUsize};
+#[cfg(not(loom))]
+pub(crate) use std::sync::{Condvar, Mutex, MutexGuard};
+
+/* ---------- */
+
+/// Blocks the current thread on `cond` while `condition` returns true or until the optional
+/// `timeout` elapses.
+///
+/// Returns the guard along with whether or not `condition` still returns true.
+pub(crate) fn wait_while<'a, T>(
+    cond: &Condvar,
+    mut guard: MutexGuard<'a, T>,
+    timeout: Option<Duration>,
+    mut condition: impl FnMut(&mut T) -> bool,
+) -> (MutexGuard<'a, T>, bool) {
+    let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+
+    while condition(&mut guard) {
+        // An overflowing timeout is as good as no timeout at all.
+        guard = match deadline {
+            None => cond.wait(guard).unwrap_or_else(PoisonError::into_inner),
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+
+                if remaining.is_zero() {
+                    return (guard, true);
+                }
+
+                cond.wait_timeout(guard, remaining)
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .0
+            }
+        };
+    }
+
+    (guard, false)
+}
@@ -1,10 +1,36 @@
 //! Types relative to the timer feature.
 
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io::Result as IoResult;
+#[cfg(all(target_os = "linux", feature = "eventfd"))]
+use std::os::fd::{AsFd, BorrowedFd};
+#[cfg(feature = "async")]
+use std::pin::Pin;
+use std::sync::atomic::Ordering;
+#[cfg(all(target_os = "linux", feature = "timerfd"))]
+use std::sync::Weak;
+use std::sync::{Arc, PoisonError};
+use std::task::Waker;
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
+use std::thread::{JoinHandle, Result as ThreadResult, Thread};
+use std::time::{Duration, Instant};
 
-use crate::Deadline;
+use crate::deadline::mul_duration;
+#[cfg(all(target_os = "linux", feature = "eventfd"))]
+use crate::eventfd::EventFd;
+use crate::seqlock::SeqLock;
+use crate::sync::{self, fence, AtomicBool, AtomicU64, AtomicUsize, Condvar, Mutex, MutexGuard};
+#[cfg(all(target_os = "linux", feature = "timerfd"))]
+use crate::timerfd::TimerFdInner;
+#[cfg(feature = "stats")]
+use crate::Stats;
+#[cfg(all(target_os = "linux", feature = "timerfd"))]
+use crate::TimerFd;
+use crate::{
+    CancelToken, Cancelled, Clock, Deadline, MissedTickBehavior, SystemClock, WaitStrategy,
+};
 
 /* ---------- */
 
@@ -12,33 +38,485 @@ use crate::Deadline;
 ///
 /// On missing ticks, the timer will burst until it catches up
 /// with the defined delay.
+///
+/// Dropping the timer disconnects its watchers, see [`Watcher::is_disconnected()`].
+///
+/// Timers measure time through a [`Clock`], the [`SystemClock`] by default.
+///
+/// Each tick can broadcast a payload of type `T` to the watchers, see [`Timer::with_payload()`].
 #[derive(Debug)]
-pub struct Timer {
-    /// The inner state of the timer, toggle on each ticks.
-    state: State,
+pub struct Timer<T = (), C = SystemClock> {
+    /// The inner state of the timer, incremented on each ticks.
+    state: State<T>,
 
     /// The deadline used to trigger the timer's ticks.
-    deadline: Deadline,
+    deadline: Deadline<C>,
+
+    /// The time remaining before the next tick when the timer got paused, `None` if running.
+    paused: Option<Duration>,
+
+    /// How long the timer waits for its watchers' acknowledgements after each tick,
+    /// `None` if the barrier mode is disabled.
+    barrier: Option<Duration>,
+
+    /// The watchers that didn't acknowledge the last tick in time.
+    late: Vec<WatcherId>,
+
+    /// The timerfd re-armed when the schedule of the timer changes, see [`Timer::timerfd()`].
+    #[cfg(all(target_os = "linux", feature = "timerfd"))]
+    timerfd: Weak<TimerFdInner>,
 }
 
 impl Timer {
     /// Returns a new timer that ticks every `delay`.
     pub fn new(delay: Duration) -> Self {
+        Self::with_clock(delay, SystemClock)
+    }
+
+    /// Returns a new timer that ticks on the wall-clock boundaries multiple of `period` since
+    /// the UNIX epoch, e.g. on every whole second with a period of one second.
+    ///
+    /// The timer re-aligns itself on the boundaries when the wall clock is stepped,
+    /// see [`Deadline::aligned()`].
+    pub fn aligned(period: Duration) -> Self {
+        let mut timer = Self::new(period);
+        timer.deadline = Deadline::aligned(period, Duration::ZERO);
+        timer
+    }
+
+    /// Spawns a new thread running a timer that ticks every `delay`.
+    ///
+    /// Use a [`TimerBuilder`] to configure the thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the OS fails to create the thread, see [`std::thread::spawn()`].
+    pub fn spawn(delay: Duration) -> TimerHandle {
+        TimerBuilder::new()
+            .spawn(Self::new(delay))
+            .expect("failed to spawn the timer thread")
+    }
+}
+
+impl<T: Copy> Timer<T> {
+    /// Returns a new timer that ticks every `delay` and broadcasts a payload to its watchers,
+    /// starting with `value`.
+    ///
+    /// The payload is updated on each tick by [`Timer::tick_with()`] and read by the watchers
+    /// without any lock, see [`Watcher::has_ticked_with()`], [`TickInfo::value`] and
+    /// [`Watcher::value()`].
+    pub fn with_payload(delay: Duration, value: T) -> Self {
+        Self::with_clock_and_payload(delay, SystemClock, value)
+    }
+}
+
+impl<C: Clock> Timer<(), C> {
+    /// Returns a new timer that ticks every `delay`, measuring time with `clock`.
+    pub fn with_clock(delay: Duration, clock: C) -> Self {
+        Self::with_clock_and_payload(delay, clock, ())
+    }
+}
+
+impl<T: Copy, C: Clock> Timer<T, C> {
+    /// Returns a new timer that ticks every `delay`, measuring time with `clock` and
+    /// broadcasting a payload to its watchers, starting with `value`.
+    pub fn with_clock_and_payload(delay: Duration, clock: C, value: T) -> Self {
         Self {
-            state: State::new(),
-            deadline: Deadline::repeat(delay),
+            state: State::new(value),
+            deadline: Deadline::repeat_with_clock(delay, MissedTickBehavior::Burst, clock),
+            paused: None,
+            barrier: None,
+            late: Vec::new(),
+            #[cfg(all(target_os = "linux", feature = "timerfd"))]
+            timerfd: Weak::new(),
+        }
+    }
+
+    /// Returns the period of the timer.
+    pub fn period(&self) -> Duration {
+        self.deadline.period().unwrap_or_default()
+    }
+
+    /// Changes the period of the timer.
+    ///
+    /// The new period takes effect at the next tick, which is rescheduled one new period
+    /// after the previous tick so the phase of the timer is kept, or on the first tick of
+    /// the new period's grid that isn't in the past, see [`Deadline::set_period()`].
+    pub fn set_period(&mut self, period: Duration) {
+        self.deadline.set_period(period);
+        self.rearm_fd();
+    }
+
+    /// Returns whether or not the timer is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused.is_some()
+    }
+
+    /// Pauses the timer.
+    ///
+    /// While paused, [`Timer::tick()`] keeps blocking the thread for one period but
+    /// doesn't notify the watchers anymore.
+    pub fn pause(&mut self) {
+        if self.paused.is_none() {
+            let now = self.deadline.clock().now();
+            self.paused = Some(self.deadline.delivery_time().saturating_duration_since(now));
+        }
+    }
+
+    /// Resumes a paused timer.
+    ///
+    /// The next tick is rescheduled so the time remaining before it is the same as
+    /// when the timer got paused.
+    pub fn resume(&mut self) {
+        if let Some(remaining) = self.paused.take() {
+            let now = self.deadline.clock().now();
+            let delivery_time = now.checked_add(remaining).unwrap_or(now);
+
+            self.deadline.reschedule(delivery_time);
+            self.rearm_fd();
         }
     }
 
+    /// Returns how the timer blocks the thread until the next tick.
+    pub fn wait_strategy(&self) -> WaitStrategy {
+        self.deadline.wait_strategy()
+    }
+
+    /// Sets how the timer blocks the thread until the next tick.
+    ///
+    /// Timers use [`WaitStrategy::Sleep`] by default. [`WaitStrategy::Hybrid`] gives
+    /// sub-100µs precision at the cost of spinning for a short time before each tick.
+    pub fn set_wait_strategy(&mut self, strategy: WaitStrategy) {
+        self.deadline.set_wait_strategy(strategy);
+    }
+
+    /// Returns how long the timer waits for its watchers' acknowledgements after each tick,
+    /// `None` if the barrier mode is disabled.
+    pub fn barrier(&self) -> Option<Duration> {
+        self.barrier
+    }
+
+    /// Enables the barrier mode if `timeout` is `Some`, disables it otherwise.
+    ///
+    /// In barrier mode, [`Timer::tick()`] blocks after notifying the watchers until every one of
+    /// them acknowledged the tick with [`Watcher::ack()`], or until `timeout` elapses. The watchers
+    /// that didn't acknowledge the tick in time are reported by [`Timer::late_watchers()`].
+    ///
+    /// Every living watcher takes part in the barrier, including the ones that are never waited on.
+    /// Divided watchers only acknowledge the ticks they are notified of.
+    pub fn set_barrier(&mut self, timeout: Option<Duration>) {
+        self.barrier = timeout;
+        self.late.clear();
+    }
+
+    /// Returns the watchers that didn't acknowledge the last tick in time, see [`Timer::set_barrier()`].
+    ///
+    /// The names of the named watchers are given by [`Timer::watcher_name()`].
+    pub fn late_watchers(&self) -> &[WatcherId] {
+        &self.late
+    }
+
+    /// Returns the statistics about how late the timer woke up.
+    ///
+    /// Missed ticks are caught up in bursts, which are reported by [`Stats::bursts()`].
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> &Stats {
+        self.deadline.stats()
+    }
+
+    /// Clears the statistics of the timer.
+    #[cfg(feature = "stats")]
+    pub fn reset_stats(&mut self) {
+        self.deadline.reset_stats();
+    }
+
     /// Returns a new watcher associated to `self`.
-    pub fn watcher(&self) -> Watcher {
-        Watcher::new(self.state.clone())
+    pub fn watcher(&self) -> Watcher<T> {
+        Watcher::new(self.state.clone(), Divider::ONE, None, None)
+    }
+
+    /// Returns a new watcher associated to `self`, named `name` for diagnostics purposes.
+    pub fn watcher_named(&self, name: impl Into<Arc<str>>) -> Watcher<T> {
+        Watcher::new(self.state.clone(), Divider::ONE, Some(name.into()), None)
+    }
+
+    /// Returns the number of living watchers associated to `self`.
+    pub fn watcher_count(&self) -> usize {
+        self.state.watcher_count()
+    }
+
+    /// Returns the name of the watcher `id`, or `None` if it is unnamed or has been dropped.
+    pub fn watcher_name(&self, id: WatcherId) -> Option<Arc<str>> {
+        self.state.watcher_name(id)
+    }
+
+    /// Returns a new watcher associated to `self` that is notified every `n` ticks only.
+    ///
+    /// This is a shorthand for `timer.watcher().divided(n)`, see [`Watcher::divided()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is `0`.
+    pub fn watcher_every(&self, n: u64) -> Watcher<T> {
+        self.watcher().divided(n)
     }
 
     /// Blocks the current thread until the next tick and notify the associated watchers.
+    ///
+    /// The time the tick was scheduled at and the time it actually fired at are reported
+    /// to the watchers, see [`TickInfo`]. The payload of the previous tick is broadcasted again.
     pub fn tick(&mut self) {
+        let value = self.state.value();
+        self.tick_with(value);
+    }
+
+    /// Blocks the current thread until the next tick and notify the associated watchers,
+    /// broadcasting `value` along with the tick.
+    ///
+    /// The value is dropped if the timer is paused.
+    pub fn tick_with(&mut self, value: T) {
+        let scheduled_at = self.deadline.delivery_time();
         self.deadline.wait();
-        self.state.toggle();
+        self.notify(1, scheduled_at, value);
+    }
+
+    /// Blocks the current thread until the next tick and notify the associated watchers,
+    /// unless `token` is cancelled first.
+    fn tick_cancellable(&mut self, token: &CancelToken) -> Result<(), Cancelled> {
+        let scheduled_at = self.deadline.delivery_time();
+        self.deadline.wait_cancellable(token)?;
+        self.notify(1, scheduled_at, self.state.value());
+        Ok(())
+    }
+
+    /// Returns a [`TimerFd`] expiring at the same times as the timer, to drive the timer from
+    /// an event loop with [`Timer::tick_fd()`] rather than blocking a thread in [`Timer::tick()`].
+    ///
+    /// The timerfd is re-armed whenever the schedule of the timer changes, i.e. on
+    /// [`Timer::set_period()`] and [`Timer::resume()`], as well as on each tick of an aligned timer
+    /// to follow the steps of the wall clock. Only the last returned timerfd is re-armed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `timerfd` couldn't be created or armed.
+    #[cfg(all(target_os = "linux", feature = "timerfd"))]
+    pub fn timerfd(&mut self) -> IoResult<TimerFd> {
+        let timerfd = TimerFd::from_deadline(&self.deadline)?;
+        self.timerfd = timerfd.downgrade();
+        Ok(timerfd)
+    }
+
+    /// Re-arms the timerfd returned by [`Timer::timerfd()`], if any, on the schedule of the timer.
+    #[inline]
+    fn rearm_fd(&self) {
+        #[cfg(all(target_os = "linux", feature = "timerfd"))]
+        if let Some(timerfd) = self.timerfd.upgrade() {
+            // Re-arming only fails on invalid settings, which the timer never produces.
+            let _ = timerfd.rearm(self.deadline.delivery_time(), self.period());
+        }
+    }
+
+    /// Notifies the associated watchers of the expirations of `timerfd` without blocking,
+    /// typically once the event loop reported it as readable.
+    ///
+    /// The expirations are counted as as many ticks, the ones but the last being reported as
+    /// missed to the watchers, see [`TickInfo::missed`]. The payload of the previous tick is
+    /// broadcasted again.
+    ///
+    /// Returns the number of expirations, `0` if `timerfd` didn't expire.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `timerfd` couldn't be read.
+    #[cfg(all(target_os = "linux", feature = "timerfd"))]
+    pub fn tick_fd(&mut self, timerfd: &mut TimerFd) -> IoResult<u64> {
+        let expirations = timerfd.read_expirations()?;
+
+        if expirations > 0 {
+            let next = timerfd.next_expiration();
+            let scheduled_at = next
+                .checked_sub(timerfd.period().unwrap_or_default())
+                .unwrap_or(next);
+
+            // The expirations delivered at once are coalesced rather than bursting.
+            #[cfg(feature = "stats")]
+            self.deadline
+                .record(scheduled_at, self.deadline.clock().now(), false);
+
+            // An aligned timer follows the wall clock rather than the monotonic schedule
+            // of the timerfd, which is re-armed accordingly.
+            if self.deadline.realign() {
+                timerfd.rearm(self.deadline.delivery_time(), self.period())?;
+            } else {
+                self.deadline.reschedule(next);
+            }
+
+            self.notify(expirations, scheduled_at, self.state.value());
+        }
+
+        Ok(expirations)
+    }
+
+    /// Notifies the associated watchers of `ticks` ticks at once, the last one being
+    /// scheduled at `scheduled_at` and broadcasting `value`, unless the timer is paused.
+    fn notify(&mut self, ticks: u64, scheduled_at: Instant, value: T) {
+        if self.paused.is_none() {
+            let fired_at = self.deadline.clock().now();
+            let period = self.period();
+
+            // SAFETY: the timer is the only one ticking its state and `&mut self` prevents
+            // concurrent ticks.
+            unsafe {
+                self.state
+                    .tick(ticks, period, scheduled_at, fired_at, value)
+            };
+
+            if let Some(timeout) = self.barrier {
+                self.late = self.state.wait_acks(timeout);
+            }
+        }
+    }
+}
+
+impl<T, C> Drop for Timer<T, C> {
+    fn drop(&mut self) {
+        self.state.disconnect();
+    }
+}
+
+/* ---------- */
+
+/// A builder to configure the thread running a [`Timer`].
+#[derive(Debug)]
+pub struct TimerBuilder {
+    /// The configuration of the thread.
+    builder: std::thread::Builder,
+}
+
+impl TimerBuilder {
+    /// Returns a new builder with the default thread configuration.
+    pub fn new() -> Self {
+        Self {
+            builder: std::thread::Builder::new(),
+        }
+    }
+
+    /// Names the thread running the timer.
+    pub fn name(self, name: impl Into<String>) -> Self {
+        Self {
+            builder: self.builder.name(name.into()),
+        }
+    }
+
+    /// Sets the size of the stack, in bytes, of the thread running the timer.
+    pub fn stack_size(self, size: usize) -> Self {
+        Self {
+            builder: self.builder.stack_size(size),
+        }
+    }
+
+    /// Spawns a new thread ticking `timer` until the returned handle is stopped or dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the OS fails to create the thread.
+    pub fn spawn<T, C>(self, timer: Timer<T, C>) -> IoResult<TimerHandle<T>>
+    where
+        T: Copy + Send + 'static,
+        C: Clock + Send + 'static,
+    {
+        let state = timer.state.clone();
+        let stop = CancelToken::new();
+        let token = stop.clone();
+
+        // The timer is moved to the heap so it doesn't weigh on a small stack,
+        // see `TimerBuilder::stack_size()`.
+        let mut timer = Box::new(timer);
+        let thread = self
+            .builder
+            .spawn(move || while timer.tick_cancellable(&token).is_ok() {})?;
+
+        Ok(TimerHandle {
+            state,
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Default for TimerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/* ---------- */
+
+/// A handle to a [`Timer`] running in its own thread, see [`Timer::spawn()`].
+///
+/// Dropping the handle stops the timer and joins its thread.
+#[derive(Debug)]
+pub struct TimerHandle<T = ()> {
+    /// The inner state of the timer.
+    state: State<T>,
+
+    /// Tells the timer's thread to stop, interrupting its wait for the next tick.
+    stop: CancelToken,
+
+    /// The timer's thread, `None` once joined.
+    thread: Option<JoinHandle<()>>,
+}
+
+impl<T: Copy> TimerHandle<T> {
+    /// Returns a new watcher associated to the timer.
+    pub fn watcher(&self) -> Watcher<T> {
+        Watcher::new(self.state.clone(), Divider::ONE, None, None)
+    }
+
+    /// Returns a new watcher associated to the timer, named `name` for diagnostics purposes.
+    pub fn watcher_named(&self, name: impl Into<Arc<str>>) -> Watcher<T> {
+        Watcher::new(self.state.clone(), Divider::ONE, Some(name.into()), None)
+    }
+}
+
+impl<T> TimerHandle<T> {
+    /// Returns the number of living watchers associated to the timer.
+    pub fn watcher_count(&self) -> usize {
+        self.state.watcher_count()
+    }
+
+    /// Returns the thread running the timer.
+    pub fn thread(&self) -> &Thread {
+        // The thread is only taken on stop or drop, which consume the handle.
+        self.thread.as_ref().map(JoinHandle::thread).unwrap()
+    }
+
+    /// Stops the timer and waits for its thread to finish.
+    ///
+    /// The timer's wait for its next tick is interrupted, so this returns promptly whatever
+    /// the period of the timer. Once stopped, the timer's watchers are disconnected.
+    ///
+    /// # Errors
+    ///
+    /// Returns the panic's payload if the timer's thread panicked.
+    pub fn stop(mut self) -> ThreadResult<()> {
+        self.join()
+    }
+
+    /// Stops the timer and waits for its thread to finish.
+    fn join(&mut self) -> ThreadResult<()> {
+        self.stop.cancel();
+
+        match self.thread.take() {
+            Some(thread) => thread.join(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T> Drop for TimerHandle<T> {
+    fn drop(&mut self) {
+        let _ = self.join();
     }
 }
 
@@ -47,134 +525,1465 @@ impl Timer {
 /// A handle associated to a [`Timer`] that is notified when the timer ticks.
 ///
 /// Watchers are safely clonable. A cloned watcher will be associated to the
-/// [`Timer`] of the original one. This is equivalent to calling [`Timer::watcher()`] twice.
-pub struct Watcher {
+/// [`Timer`] of the original one and inherits its pending ticks, see [`Watcher::fresh()`]
+/// to get a watcher without pending ticks.
+///
+/// A watcher can be notified only every n-th tick of its timer, see [`Watcher::divided()`].
+pub struct Watcher<T = ()> {
     /// The inner state of the associated [`Timer`].
-    state: State,
+    state: State<T>,
 
-    /// The prévious value of the state.
-    prev_state: bool,
+    /// The identifier of the watcher among the ones of its [`Timer`].
+    id: WatcherId,
+
+    /// The name of the watcher, if any.
+    name: Option<Arc<str>>,
+
+    /// The number of ticks of the state when the watcher last checked it.
+    last_seq: u64,
+
+    /// The ticks the watcher is notified of.
+    divider: Divider,
+
+    /// The stamp of the last tick a divided watcher was notified of, recorded by the [`Timer`]
+    /// since the last tick of the timer isn't necessarily one of them.
+    stamp: Option<Arc<SeqLock<Stamp<T>>>>,
+
+    /// The eventfd signaled on the watcher's ticks, created by [`Watcher::as_event_fd()`].
+    #[cfg(all(target_os = "linux", feature = "eventfd"))]
+    event_fd: Option<Arc<EventFd>>,
 }
 
-impl Watcher {
-    /// Returns a new watcher associated to a [`Timer`].
-    fn new(state: State) -> Self {
-        let prev_state = state.value();
-        Self { state, prev_state }
+impl<T: Copy> Watcher<T> {
+    /// Returns a new watcher associated to a [`Timer`], notified of the ticks selected by `divider`.
+    ///
+    /// A divided watcher starts from the `inherited` stamp of the watcher it is cloned from, if any.
+    fn new(
+        state: State<T>,
+        divider: Divider,
+        name: Option<Arc<str>>,
+        inherited: Option<&SeqLock<Stamp<T>>>,
+    ) -> Self {
+        let (id, last_seq, stamp) = state.register(divider, name.clone(), inherited);
+
+        Self {
+            state,
+            id,
+            name,
+            last_seq,
+            divider,
+            stamp,
+            #[cfg(all(target_os = "linux", feature = "eventfd"))]
+            event_fd: None,
+        }
+    }
+
+    /// Returns a new watcher associated to the same [`Timer`], with the same divider and name
+    /// but without any pending tick.
+    ///
+    /// Unlike [`Watcher::clone()`], the new watcher is only notified of the ticks occuring after its creation.
+    pub fn fresh(&self) -> Self {
+        Self::new(self.state.clone(), self.divider, self.name.clone(), None)
+    }
+
+    /// Returns the name of the watcher, see [`Timer::watcher_named()`].
+    ///
+    /// Cloned watchers keep the name of the original one.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the identifier of the watcher among the ones of its [`Timer`].
+    ///
+    /// Cloned watchers get their own identifier.
+    pub fn id(&self) -> WatcherId {
+        self.id
+    }
+
+    /// Makes the watcher notified every `n` ticks of its [`Timer`] only.
+    ///
+    /// By default, the watcher is notified on the ticks whose sequence number is a multiple of `n`.
+    /// Since the sequence numbers are shared by all the watchers of a timer, watchers with
+    /// the same divider are always notified on the same ticks. See [`Watcher::with_phase()`]
+    /// to offset the notifications.
+    ///
+    /// The ticks reported by [`Watcher::ticks_since_last()`], [`Watcher::poll()`] and the
+    /// waiting methods only count the ticks the watcher is notified of.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is `0`.
+    pub fn divided(mut self, n: u64) -> Self {
+        assert!(n > 0, "a watcher's divider must be greater than 0");
+
+        self.divider.n = n;
+        self.stamp = self.state.set_divider(self.id, self.divider);
+        self
+    }
+
+    /// Offsets the notifications of a divided watcher by `phase` ticks.
+    ///
+    /// The watcher is then notified on the ticks whose sequence number modulo the divider
+    /// equals `phase` modulo the divider.
+    pub fn with_phase(mut self, phase: u64) -> Self {
+        self.divider.phase = phase;
+        self.stamp = self.state.set_divider(self.id, self.divider);
+        self
     }
 
     /// Returns whether or not the associated [`Timer`] has ticked.
+    ///
+    /// If the timer ticked multiple times since the last check, all ticks are consumed at once.
+    /// Use [`Watcher::ticks_since_last()`] or [`Watcher::poll()`] to know how many ticks
+    /// occured, or [`Watcher::has_ticked_with()`] to get the payload of the last notified tick.
     pub fn has_ticked(&mut self) -> bool {
-        if self.state.value() != self.prev_state {
-            self.prev_state = !self.prev_state;
-            return true;
+        self.ticks_since_last() > 0
+    }
+
+    /// Returns the payload of the last tick the watcher was notified of if the associated
+    /// [`Timer`] has ticked since the last check, see [`Timer::tick_with()`].
+    ///
+    /// This is [`Watcher::has_ticked()`] for the timers broadcasting a payload: all ticks are
+    /// consumed at once and the payload can't be torn by a concurrent tick.
+    pub fn has_ticked_with(&mut self) -> Option<T> {
+        self.poll().map(|info| info.value)
+    }
+
+    /// Returns the number of times the associated [`Timer`] has ticked since the last check.
+    pub fn ticks_since_last(&mut self) -> u64 {
+        let seq = self.state.ticks();
+        self.advance(seq)
+    }
+
+    /// Moves the last check of the watcher to the tick `seq` and returns the number of
+    /// notifications since the previous check.
+    #[inline]
+    fn advance(&mut self, seq: u64) -> u64 {
+        // The stamp of a tick is published right before the tick count is incremented,
+        // so the tick count might lag behind the last check.
+        let seq = seq.max(self.last_seq);
+        let ticks = self.divider.notifications(seq) - self.divider.notifications(self.last_seq);
+        self.last_seq = seq;
+
+        if ticks > 0 {
+            self.clear_event_fd();
+        }
+
+        ticks
+    }
+
+    /// Clears the readiness of the watcher's eventfd, if any, unless the watcher still has
+    /// pending ticks or is disconnected.
+    #[inline]
+    fn clear_event_fd(&self) {
+        #[cfg(all(target_os = "linux", feature = "eventfd"))]
+        if let Some(event_fd) = &self.event_fd {
+            event_fd.clear();
+
+            // A tick occuring right before the clear must not lose its readiness.
+            if self.has_pending() || self.is_disconnected() {
+                event_fd.signal();
+            }
+        }
+    }
+
+    /// Returns whether or not the watcher has been notified of a tick since the last check,
+    /// without consuming it.
+    #[inline]
+    pub(crate) fn has_pending(&self) -> bool {
+        let seq = self.state.ticks().max(self.last_seq);
+        self.divider.notifications(seq) > self.divider.notifications(self.last_seq)
+    }
+
+    /// Returns the time the last tick the watcher was notified of was scheduled at, `None` if
+    /// there is none.
+    #[inline]
+    pub(crate) fn last_scheduled_at(&self) -> Option<Instant> {
+        self.last_stamp().map(|stamp| stamp.scheduled_at)
+    }
+
+    /// Returns the stamp of the last tick the watcher was notified of, `None` if there is none.
+    #[inline]
+    fn last_stamp(&self) -> Option<Stamp<T>> {
+        match &self.stamp {
+            Some(stamp) => Some(stamp.read()).filter(|stamp| stamp.seq > 0),
+            None => self.state.last_tick(),
+        }
+    }
+
+    /// Registers `waker` to be woken up on the next tick of the associated [`Timer`]
+    /// or once it is dropped.
+    #[inline]
+    pub(crate) fn register_waker(&self, waker: &Waker) {
+        self.state.register_waker(waker);
+    }
+
+    /// Returns the number of wakers registered to the associated [`Timer`].
+    #[cfg(test)]
+    pub(crate) fn waker_count(&self) -> usize {
+        self.state.waker_count()
+    }
+
+    /// Returns informations about the last tick the watcher was notified of if the associated
+    /// [`Timer`] has ticked since the last check.
+    pub fn poll(&mut self) -> Option<TickInfo<T>> {
+        let stamp = self.last_stamp()?;
+        let missed = self.advance(stamp.seq).checked_sub(1)?;
+
+        Some(TickInfo {
+            seq: stamp.seq,
+            missed,
+            scheduled_at: stamp.scheduled_at,
+            fired_at: stamp.fired_at,
+            value: stamp.value,
+        })
+    }
+
+    /// Returns the payload broadcasted by the latest tick of the associated [`Timer`],
+    /// or its initial payload if it never ticked.
+    ///
+    /// This doesn't consume any tick.
+    pub fn value(&self) -> T {
+        self.state.value()
+    }
+
+    /// Acknowledges the ticks consumed so far, telling a [`Timer`] in barrier mode that the work
+    /// associated to them is done, see [`Timer::set_barrier()`].
+    pub fn ack(&self) {
+        self.state.ack(self.id, self.last_seq);
+    }
+
+    /// Returns an eventfd that becomes readable when the watcher is notified of a tick,
+    /// so it can be multiplexed with sockets in a `poll`/`epoll` loop.
+    ///
+    /// The eventfd stays readable until the pending ticks are consumed, either by
+    /// [`Watcher::read_event_fd()`] or by any other method consuming them, e.g. [`Watcher::poll()`].
+    /// The eventfd is created on the first call and is readable right away if the watcher has
+    /// pending ticks. It also becomes readable for good once the timer is dropped,
+    /// see [`Watcher::is_disconnected()`].
+    ///
+    /// Cloned watchers don't share the eventfd of the original one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the eventfd couldn't be created.
+    #[cfg(all(target_os = "linux", feature = "eventfd"))]
+    pub fn as_event_fd(&mut self) -> IoResult<BorrowedFd<'_>> {
+        let event_fd = match self.event_fd.take() {
+            Some(event_fd) => event_fd,
+            None => {
+                let event_fd = Arc::new(EventFd::new()?);
+                self.state.set_event_fd(self.id, Arc::clone(&event_fd));
+
+                // The ticks occuring before the registration must be signaled as well.
+                if self.has_pending() || self.is_disconnected() {
+                    event_fd.signal();
+                }
+
+                event_fd
+            }
+        };
+
+        let event_fd: &EventFd = self.event_fd.insert(event_fd);
+        Ok(event_fd.as_fd())
+    }
+
+    /// Consumes the pending ticks once the eventfd returned by [`Watcher::as_event_fd()`]
+    /// has been reported as readable, clearing its readiness.
+    ///
+    /// Returns informations about the last tick, or `None` if the watcher has no pending ticks.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Disconnected`] if the timer has been dropped and every tick has been consumed,
+    /// in which case the eventfd stays readable and should be removed from the event loop.
+    #[cfg(all(target_os = "linux", feature = "eventfd"))]
+    pub fn read_event_fd(&mut self) -> Result<Option<TickInfo<T>>, Disconnected> {
+        let disconnected = self.is_disconnected();
+
+        if let Some(info) = self.poll() {
+            return Ok(Some(info));
+        }
+
+        if disconnected {
+            return Err(Disconnected);
+        }
+
+        // The eventfd might be readable without pending ticks when a tick consumed right
+        // after its count got incremented signals the eventfd late.
+        self.clear_event_fd();
+        Ok(None)
+    }
+
+    /// Returns whether or not the associated [`Timer`] has been dropped.
+    ///
+    /// A disconnected watcher will never be notified again, although the ticks that occured
+    /// before the disconnection can still be consumed.
+    pub fn is_disconnected(&self) -> bool {
+        self.state.is_disconnected()
+    }
+
+    /// Blocks the current thread until the associated [`Timer`] ticks.
+    ///
+    /// Returns immediately if the timer ticked since the last check.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Disconnected`] if the timer has been dropped and every tick has been consumed.
+    pub fn wait(&mut self) -> Result<TickInfo<T>, Disconnected> {
+        self.wait_tick(None).map_err(|_| Disconnected)
+    }
+
+    /// Blocks the current thread until the associated [`Timer`] ticks or `timeout` elapses.
+    ///
+    /// Returns immediately if the timer ticked since the last check.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WaitTimeoutError::Timeout`] if the timer didn't tick in time, or
+    /// [`WaitTimeoutError::Disconnected`] if the timer has been dropped and every tick has been consumed.
+    pub fn wait_timeout(&mut self, timeout: Duration) -> Result<TickInfo<T>, WaitTimeoutError> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.wait_tick(Some(deadline)),
+            None => self.wait_tick(None),
+        }
+    }
+
+    /// Blocks the current thread until the associated [`Timer`] ticks or the optional `deadline` is reached.
+    fn wait_tick(&mut self, deadline: Option<Instant>) -> Result<TickInfo<T>, WaitTimeoutError> {
+        loop {
+            // Checking the disconnection first guarantees that the last ticks of the timer
+            // are reported before the disconnection.
+            let disconnected = self.is_disconnected();
+
+            if let Some(info) = self.poll() {
+                return Ok(info);
+            }
+
+            if disconnected {
+                return Err(WaitTimeoutError::Disconnected);
+            }
+
+            let timeout = match deadline {
+                Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                    Some(timeout) if !timeout.is_zero() => Some(timeout),
+                    _ => return Err(WaitTimeoutError::Timeout),
+                },
+                None => None,
+            };
+
+            self.state.wait_event(self.last_seq, timeout);
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: Copy> futures_core::Stream for Watcher<T> {
+    type Item = TickInfo<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(info) = self.poll() {
+            return Poll::Ready(Some(info));
+        }
+
+        self.state.register_waker(cx.waker());
+
+        // The timer might have ticked or been dropped before the waker got registered.
+        let disconnected = self.is_disconnected();
+
+        match self.poll() {
+            Some(info) => Poll::Ready(Some(info)),
+            None if disconnected => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl<T: Copy> Clone for Watcher<T> {
+    /// Returns a new watcher associated to the same [`Timer`], with the same pending ticks.
+    ///
+    /// Use [`Watcher::fresh()`] to ignore the pending ticks instead.
+    fn clone(&self) -> Self {
+        let mut clone = Self::new(
+            self.state.clone(),
+            self.divider,
+            self.name.clone(),
+            self.stamp.as_deref(),
+        );
+
+        // The clone starts from the last check of `self` rather than from the current tick,
+        // so a tick occuring during the clone is seen by both watchers.
+        clone.last_seq = self.last_seq;
+        clone
+    }
+}
+
+impl<T> Drop for Watcher<T> {
+    fn drop(&mut self) {
+        self.state.unregister(self.id);
+    }
+}
+
+/* ---------- */
+
+/// The identifier of a [`Watcher`] among the ones of its [`Timer`], see [`Watcher::id()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WatcherId(u64);
+
+/// Selects the ticks a [`Watcher`] is notified of, see [`Watcher::divided()`].
+#[derive(Debug, Clone, Copy)]
+struct Divider {
+    /// The watcher is notified every `n` ticks.
+    n: u64,
+
+    /// The offset, in ticks, of the notifications.
+    phase: u64,
+}
+
+impl Divider {
+    /// The divider notifying every tick.
+    const ONE: Self = Self { n: 1, phase: 0 };
+
+    /// Returns the number of notifications in the first `seq` ticks of the timer, up to a constant.
+    #[inline]
+    fn notifications(&self, seq: u64) -> u64 {
+        if self.n == 1 {
+            return seq;
         }
 
-        false
+        // The watcher is notified on the ticks `phase + k * n`.
+        seq / self.n + u64::from(seq % self.n >= self.phase % self.n)
+    }
+
+    /// Returns the sequence number of the last notified tick up to `seq`, or `0` if there is none.
+    #[inline]
+    fn last_notified(&self, seq: u64) -> u64 {
+        let offset = (seq % self.n + self.n - self.phase % self.n) % self.n;
+        seq.saturating_sub(offset)
     }
 }
 
-impl Clone for Watcher {
-    fn clone(&self) -> Self {
-        // FIXME: What happens if the timer ticks between the the clone and the prev_state ?
-        // We might miss 2 ticks here, is it a problem ?
-        // We probably should get the prev_value *before* the clone itself.
-        let state = self.state.clone();
-        let prev_state = state.value();
+/* ---------- */
+
+/// Informations about a tick of a [`Timer`], as seen by a [`Watcher`].
+///
+/// The reported tick is the last tick the watcher was notified of, which is the latest tick
+/// of the timer unless the watcher is divided, see [`Watcher::divided()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickInfo<T = ()> {
+    /// The sequence number of the tick, starting at `1` for the first tick of the timer.
+    pub seq: u64,
+
+    /// The number of ticks that occured before this one since the last check of the watcher.
+    pub missed: u64,
+
+    /// The time the tick was scheduled at.
+    pub scheduled_at: Instant,
+
+    /// The time the tick was actually fired at, after the [`Timer`] woke up.
+    ///
+    /// The difference with [`TickInfo::scheduled_at`] is the lateness of the timer, while the
+    /// time elapsed since `fired_at` is the latency of the watcher.
+    pub fired_at: Instant,
+
+    /// The payload broadcasted with the tick, see [`Timer::tick_with()`].
+    pub value: T,
+}
+
+/* ---------- */
+
+/// The error returned by [`Watcher::wait()`] when the associated [`Timer`] has been dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Disconnected;
+
+impl Display for Disconnected {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "the timer has been dropped")
+    }
+}
+
+impl Error for Disconnected {}
+
+/// The error returned by [`Watcher::wait_timeout()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitTimeoutError {
+    /// The timer didn't tick before the timeout elapsed.
+    Timeout,
+    /// The timer has been dropped.
+    Disconnected,
+}
+
+impl Display for WaitTimeoutError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Timeout => write!(f, "timed out waiting for the timer to tick"),
+            Self::Disconnected => write!(f, "{Disconnected}"),
+        }
+    }
+}
+
+impl Error for WaitTimeoutError {}
+
+impl From<Disconnected> for WaitTimeoutError {
+    fn from(_: Disconnected) -> Self {
+        Self::Disconnected
+    }
+}
+
+/* ---------- */
+
+/// Inner state of the [`Timer`] and [`Watcher`] types.
+#[derive(Debug)]
+struct State<T = ()>(Arc<StateInner<T>>);
+
+impl<T: Copy> State<T> {
+    /// Returns a new state whose initial payload is `value`.
+    #[inline]
+    fn new(value: T) -> Self {
+        let now = Instant::now();
+        let stamp = Stamp {
+            seq: 0,
+            scheduled_at: now,
+            fired_at: now,
+            value,
+        };
+
+        Self(Arc::new(StateInner {
+            ticks: AtomicU64::default(),
+            last: SeqLock::new(stamp),
+            disconnected: AtomicBool::default(),
+            parked: AtomicUsize::default(),
+            lock: Mutex::default(),
+            cond: Condvar::default(),
+            registry: Mutex::new(Registry {
+                next_id: 0,
+                entries: Vec::new(),
+            }),
+            acked: Condvar::default(),
+            has_divided: AtomicBool::default(),
+            #[cfg(all(target_os = "linux", feature = "eventfd"))]
+            has_event_fds: AtomicBool::default(),
+            has_wakers: AtomicBool::default(),
+            wakers: Mutex::default(),
+        }))
+    }
+
+    /// Adds `ticks` ticks `period` apart to the state's tick count, records the timestamps
+    /// and the payload of the last tick and wake the parked watchers up.
+    ///
+    /// # Safety
+    ///
+    /// The state must be ticked by a single thread at a time.
+    #[inline]
+    unsafe fn tick(
+        &self,
+        ticks: u64,
+        period: Duration,
+        scheduled_at: Instant,
+        fired_at: Instant,
+        value: T,
+    ) {
+        let prev = self.ticks();
+        let stamp = Stamp {
+            seq: prev + ticks,
+            scheduled_at,
+            fired_at,
+            value,
+        };
+
+        // SAFETY: the caller guarantees there is a single writer. The stamp is published
+        // before the tick count so the watchers noticing the tick always read its stamp.
+        unsafe { self.0.last.write(stamp) };
+
+        // Pairs with the fence of `divided_stamp()`: either the timer sees the divided watcher,
+        // or the watcher starts from this stamp.
+        fence(Ordering::SeqCst);
+
+        if self.0.has_divided.load(Ordering::SeqCst) {
+            self.stamp_divided(prev, stamp, period);
+        }
+
+        self.0.ticks.fetch_add(ticks, Ordering::SeqCst);
+
+        self.notify();
+
+        #[cfg(all(target_os = "linux", feature = "eventfd"))]
+        self.signal_event_fds(|entry| {
+            entry.divider.notifications(stamp.seq) > entry.divider.notifications(stamp.seq - ticks)
+        });
+    }
+
+    /// Records the stamps of the divided watchers notified of a tick after the tick `prev`
+    /// up to the tick `last`, the ticks being `period` apart.
+    fn stamp_divided(&self, prev: u64, last: Stamp<T>, period: Duration) {
+        for entry in &self.registry().entries {
+            let Some(stamp) = &entry.stamp else {
+                continue;
+            };
+
+            let seq = entry.divider.last_notified(last.seq);
+
+            if seq > prev {
+                let early = mul_duration(period, last.seq - seq);
+                let scheduled_at = last.scheduled_at.checked_sub(early);
+
+                // SAFETY: the stamps of the divided watchers are only written here,
+                // while holding the registry lock.
+                unsafe {
+                    stamp.write(Stamp {
+                        seq,
+                        scheduled_at: scheduled_at.unwrap_or(last.scheduled_at),
+                        ..last
+                    });
+                }
+            }
+        }
+    }
+
+    /// Registers a new watcher notified of the ticks selected by `divider` and named `name`.
+    ///
+    /// Returns the identifier of the watcher along with the current tick count and, if it is
+    /// divided, the stamp of its last notified tick, starting from the `inherited` one if any.
+    fn register(
+        &self,
+        divider: Divider,
+        name: Option<Arc<str>>,
+        inherited: Option<&SeqLock<Stamp<T>>>,
+    ) -> (WatcherId, u64, Option<Arc<SeqLock<Stamp<T>>>>) {
+        let mut registry = self.registry();
+        let id = WatcherId(registry.next_id);
+        let seq = self.ticks();
+        let stamp = (divider.n > 1).then(|| self.divided_stamp(inherited));
+
+        registry.next_id += 1;
+        registry.entries.push(Entry {
+            id,
+            name,
+            divider,
+            acked: seq,
+            stamp: stamp.clone(),
+            #[cfg(all(target_os = "linux", feature = "eventfd"))]
+            event_fd: None,
+        });
+
+        (id, seq, stamp)
+    }
+
+    /// Changes the ticks the watcher `id` is notified of.
+    ///
+    /// Returns the stamp of the last tick the watcher is notified of if it is divided.
+    fn set_divider(&self, id: WatcherId, divider: Divider) -> Option<Arc<SeqLock<Stamp<T>>>> {
+        let mut registry = self.registry();
+        let entry = registry.entry(id)?;
+
+        entry.divider = divider;
+        entry.stamp = match entry.stamp.take() {
+            _ if divider.n == 1 => None,
+            Some(stamp) => Some(stamp),
+            None => Some(self.divided_stamp(None)),
+        };
+
+        entry.stamp.clone()
+    }
+
+    /// Returns the stamp of a new divided watcher, starting from the `inherited` one if any
+    /// or from the last tick otherwise.
+    ///
+    /// The registry must be locked so the timer doesn't tick meanwhile.
+    fn divided_stamp(&self, inherited: Option<&SeqLock<Stamp<T>>>) -> Arc<SeqLock<Stamp<T>>> {
+        self.0.has_divided.store(true, Ordering::SeqCst);
+        // Pairs with the fence of `tick()`: either the timer sees the divided watcher,
+        // or the watcher starts from the stamp of the tick.
+        fence(Ordering::SeqCst);
+
+        let stamp = inherited.map_or_else(|| self.0.last.read(), SeqLock::read);
+        Arc::new(SeqLock::new(stamp))
+    }
+
+    /// Returns the stamp of the last tick, `None` if the [`Timer`] never ticked.
+    #[inline]
+    fn last_tick(&self) -> Option<Stamp<T>> {
+        let stamp = self.0.last.read();
+        (stamp.seq > 0).then_some(stamp)
+    }
+
+    /// Returns the payload of the last tick, or the initial one if the [`Timer`] never ticked.
+    #[inline]
+    fn value(&self) -> T {
+        self.0.last.read().value
+    }
+}
+
+impl<T> State<T> {
+    /// Returns the number of registered watchers.
+    fn watcher_count(&self) -> usize {
+        self.registry().entries.len()
+    }
+
+    /// Returns the name of the watcher `id`.
+    fn watcher_name(&self, id: WatcherId) -> Option<Arc<str>> {
+        self.registry().entry(id)?.name.clone()
+    }
+
+    /// Unregisters the watcher `id`.
+    fn unregister(&self, id: WatcherId) {
+        self.registry().entries.retain(|entry| entry.id != id);
+        self.0.acked.notify_all();
+    }
+
+    /// Acknowledges the ticks of the watcher `id` up to `seq`.
+    fn ack(&self, id: WatcherId, seq: u64) {
+        if let Some(entry) = self.registry().entry(id) {
+            entry.acked = entry.acked.max(seq);
+        }
+
+        self.0.acked.notify_all();
+    }
+
+    /// Registers the eventfd signaled on the ticks of the watcher `id`.
+    #[cfg(all(target_os = "linux", feature = "eventfd"))]
+    fn set_event_fd(&self, id: WatcherId, event_fd: Arc<EventFd>) {
+        if let Some(entry) = self.registry().entry(id) {
+            entry.event_fd = Some(event_fd);
+        }
+
+        self.0.has_event_fds.store(true, Ordering::SeqCst);
+        // Pairs with the fence of `notify()`: either the timer signals the eventfd,
+        // or the watcher sees the tick.
+        fence(Ordering::SeqCst);
+    }
+
+    /// Signals the eventfds of the watchers selected by `filter`.
+    #[cfg(all(target_os = "linux", feature = "eventfd"))]
+    fn signal_event_fds(&self, filter: impl Fn(&Entry<T>) -> bool) {
+        if !self.0.has_event_fds.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let registry = self.registry();
+        let event_fds = registry.entries.iter().filter(|entry| filter(entry));
+
+        for event_fd in event_fds.filter_map(|entry| entry.event_fd.as_deref()) {
+            event_fd.signal();
+        }
+    }
+
+    /// Blocks the current thread until every watcher acknowledged the last tick
+    /// or `timeout` elapses.
+    ///
+    /// Returns the watchers that didn't acknowledge the last tick.
+    fn wait_acks(&self, timeout: Duration) -> Vec<WatcherId> {
+        let seq = self.ticks();
+        let (registry, _) =
+            sync::wait_while(&self.0.acked, self.registry(), Some(timeout), |registry| {
+                registry.entries.iter().any(|entry| entry.is_late(seq))
+            });
+
+        registry
+            .entries
+            .iter()
+            .filter(|entry| entry.is_late(seq))
+            .map(|entry| entry.id)
+            .collect()
+    }
+
+    /// Locks the registry of the watchers.
+    #[inline]
+    fn registry(&self) -> MutexGuard<'_, Registry<T>> {
+        self.0
+            .registry
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Marks the [`Timer`] as dropped and wake the parked watchers up.
+    #[inline]
+    fn disconnect(&self) {
+        self.0.disconnected.store(true, Ordering::SeqCst);
+        self.notify();
+
+        #[cfg(all(target_os = "linux", feature = "eventfd"))]
+        self.signal_event_fds(|_| true);
+    }
+
+    /// Returns whether or not the [`Timer`] has been dropped.
+    #[inline]
+    fn is_disconnected(&self) -> bool {
+        self.0.disconnected.load(Ordering::Acquire)
+    }
+
+    /// Wakes the parked watchers up.
+    #[inline]
+    fn notify(&self) {
+        // Pairs with the fence of `wait_event()`: either the parked watcher sees the new value,
+        // or this sees the parked watcher.
+        fence(Ordering::SeqCst);
+
+        // Skip the lock when nobody waits, which keeps the ticks cheap for polling watchers.
+        if self.0.parked.load(Ordering::SeqCst) > 0 {
+            // Taking the lock guarantees that every parked watcher either waits on
+            // the condvar or will see the new value.
+            drop(self.0.lock.lock().unwrap_or_else(PoisonError::into_inner));
+            self.0.cond.notify_all();
+        }
+
+        if self.0.has_wakers.load(Ordering::SeqCst) {
+            self.wake_tasks();
+        }
+    }
+
+    /// Registers `waker` to be woken up on the next tick.
+    fn register_waker(&self, waker: &Waker) {
+        let mut wakers = self.0.wakers.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if !wakers.iter().any(|registered| registered.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+
+        self.0.has_wakers.store(true, Ordering::SeqCst);
+        fence(Ordering::SeqCst);
+    }
+
+    /// Returns the number of registered wakers.
+    #[cfg(test)]
+    fn waker_count(&self) -> usize {
+        self.0
+            .wakers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Wakes the registered wakers up.
+    fn wake_tasks(&self) {
+        let wakers = {
+            let mut wakers = self.0.wakers.lock().unwrap_or_else(PoisonError::into_inner);
+            self.0.has_wakers.store(false, Ordering::SeqCst);
+            std::mem::take(&mut *wakers)
+        };
+
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    /// Returns the number of ticks since the creation of the state.
+    #[inline]
+    fn ticks(&self) -> u64 {
+        self.0.ticks.load(Ordering::Acquire)
+    }
+
+    /// Blocks the current thread until the state's tick count exceeds `prev`, the [`Timer`]
+    /// is dropped or the optional `timeout` elapses.
+    ///
+    /// Returns whether or not the tick count changed or the timer has been dropped.
+    fn wait_event(&self, prev: u64, timeout: Option<Duration>) -> bool {
+        let inner = &*self.0;
+        // The tick count lags behind `prev` when the watcher already consumed the stamp of a tick
+        // whose count isn't incremented yet, which must not be mistaken for a new tick.
+        let unchanged = |_: &mut ()| {
+            inner.ticks.load(Ordering::SeqCst) <= prev && !inner.disconnected.load(Ordering::SeqCst)
+        };
+
+        inner.parked.fetch_add(1, Ordering::SeqCst);
+        fence(Ordering::SeqCst);
+        let guard = inner.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        let (guard, timed_out) = sync::wait_while(&inner.cond, guard, timeout, unchanged);
+
+        drop(guard);
+        inner.parked.fetch_sub(1, Ordering::SeqCst);
+        !timed_out
+    }
+}
+
+impl<T> Clone for State<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+/// The data shared by a [`State`]'s handles.
+#[derive(Debug)]
+struct StateInner<T> {
+    /// The number of ticks, incremented on each tick.
+    ticks: AtomicU64,
+
+    /// The stamp of the last tick.
+    last: SeqLock<Stamp<T>>,
+
+    /// Whether or not the [`Timer`] has been dropped.
+    disconnected: AtomicBool,
+
+    /// The number of watchers waiting for a tick.
+    parked: AtomicUsize,
+
+    /// The lock associated to `cond`.
+    lock: Mutex<()>,
+
+    /// Wakes the parked watchers up on ticks.
+    cond: Condvar,
+
+    /// The watchers associated to the [`Timer`].
+    registry: Mutex<Registry<T>>,
+
+    /// Wakes the [`Timer`] up on acknowledgements.
+    acked: Condvar,
+
+    /// Whether or not a divided watcher has been registered, which spares locking the registry
+    /// on each tick.
+    has_divided: AtomicBool,
+
+    /// Whether or not a watcher registered an eventfd, which spares locking the registry
+    /// on each tick.
+    #[cfg(all(target_os = "linux", feature = "eventfd"))]
+    has_event_fds: AtomicBool,
+
+    /// Whether or not `wakers` is empty, which spares locking it on each tick.
+    has_wakers: AtomicBool,
+
+    /// The wakers of the tasks or threads waiting for a tick.
+    wakers: Mutex<Vec<Waker>>,
+}
+
+/// The watchers associated to a [`Timer`].
+#[derive(Debug)]
+struct Registry<T> {
+    /// The identifier of the next registered watcher.
+    next_id: u64,
+
+    /// The registered watchers.
+    entries: Vec<Entry<T>>,
+}
+
+impl<T> Registry<T> {
+    /// Returns the entry of the watcher `id`.
+    #[inline]
+    fn entry(&mut self, id: WatcherId) -> Option<&mut Entry<T>> {
+        self.entries.iter_mut().find(|entry| entry.id == id)
+    }
+}
+
+/// A watcher registered in a [`Registry`].
+#[derive(Debug)]
+struct Entry<T> {
+    /// The identifier of the watcher.
+    id: WatcherId,
+
+    /// The name of the watcher, if any.
+    name: Option<Arc<str>>,
+
+    /// The ticks the watcher is notified of.
+    divider: Divider,
+
+    /// The last tick acknowledged by the watcher.
+    acked: u64,
+
+    /// The stamp of the last tick the watcher was notified of if it is divided, shared with
+    /// the [`Watcher`].
+    stamp: Option<Arc<SeqLock<Stamp<T>>>>,
+
+    /// The eventfd signaled on the watcher's ticks, if any.
+    #[cfg(all(target_os = "linux", feature = "eventfd"))]
+    event_fd: Option<Arc<EventFd>>,
+}
+
+impl<T> Entry<T> {
+    /// Returns whether or not the watcher didn't acknowledge the last tick it was notified of,
+    /// `seq` being the current tick count.
+    #[inline]
+    fn is_late(&self, seq: u64) -> bool {
+        self.acked < self.divider.last_notified(seq)
+    }
+}
+
+/// The timestamps and the payload of a tick, recorded by the [`Timer`].
+#[derive(Debug, Clone, Copy)]
+struct Stamp<T> {
+    /// The sequence number of the tick, `0` before the first tick.
+    seq: u64,
+
+    /// The time the tick was scheduled at.
+    scheduled_at: Instant,
+
+    /// The time the tick was fired at.
+    fired_at: Instant,
+
+    /// The payload of the tick.
+    value: T,
+}
+
+#[cfg(test)]
+impl PartialEq<u64> for State {
+    #[inline]
+    fn eq(&self, other: &u64) -> bool {
+        self.ticks() == *other
+    }
+}
+
+/* ---------- */
+
+#[cfg(test)]
+mod state {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let new = State::new(());
+        assert_eq!(new, 0);
+    }
+
+    #[test]
+    fn tick() {
+        let new = State::new(());
+        let now = Instant::now();
+        assert_eq!(new, 0);
+        assert!(new.last_tick().is_none());
+
+        // SAFETY: single writer.
+        unsafe { new.tick(1, Duration::ZERO, now, now, ()) };
+        assert_eq!(new, 1);
+
+        // SAFETY: single writer.
+        unsafe { new.tick(1, Duration::ZERO, now, now + Duration::from_millis(1), ()) };
+        assert_eq!(new, 2);
+
+        let stamp = new.last_tick().unwrap();
+        assert_eq!(stamp.seq, 2);
+        assert_eq!(
+            stamp.fired_at - stamp.scheduled_at,
+            Duration::from_millis(1)
+        );
+    }
+
+    #[test]
+    fn divided() {
+        let new = State::new(0);
+        let period = Duration::from_millis(100);
+        let start = Instant::now();
+        let mut every_3 = Watcher::new(new.clone(), Divider { n: 3, phase: 0 }, None, None);
+        let mut every_3_clone = every_3.clone();
+
+        // SAFETY: single writer.
+        unsafe { new.tick(5, period, start, start, 5) };
+
+        let info = every_3.poll().unwrap();
+        assert_eq!((info.seq, info.missed, info.value), (3, 0, 5));
+        assert_eq!(start - info.scheduled_at, period * 2);
+        assert_eq!(info.fired_at, start);
+
+        // SAFETY: single writer.
+        unsafe { new.tick(2, period, start + period * 2, start + period * 2, 7) };
+
+        let info = every_3.poll().unwrap();
+        assert_eq!((info.seq, info.missed, info.value), (6, 0, 7));
+        assert_eq!(info.scheduled_at, start + period);
+
+        // SAFETY: single writer.
+        unsafe { new.tick(1, period, start + period * 3, start + period * 3, 8) };
+        assert!(every_3.poll().is_none());
+
+        let info = every_3_clone.poll().unwrap();
+        assert_eq!((info.seq, info.missed), (6, 1));
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod timer {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::deadline::boundary_distance;
+    use crate::ManualClock;
+
+    #[test]
+    fn tick_delay() {
+        let now = Instant::now();
+        let mut timer = Timer::new(Duration::from_millis(100));
+
+        for count in 0..5 {
+            timer.tick();
+
+            let elapsed = now.elapsed();
+            assert!(
+                now.elapsed() >= Duration::from_millis(100 * count),
+                "elapsed = {elapsed:?}"
+            )
+        }
+    }
+
+    #[test]
+    fn tick_spin() {
+        let now = Instant::now();
+        let mut timer = Timer::new(Duration::from_millis(20));
+        timer.set_wait_strategy(WaitStrategy::Spin);
+
+        for count in 1..=5 {
+            timer.tick();
+
+            let late = now.elapsed() - Duration::from_millis(20 * count);
+            assert!(late < Duration::from_millis(10), "late = {late:?}");
+        }
+    }
+
+    #[test]
+    fn aligned() {
+        let period = Duration::from_millis(50);
+        let mut timer = Timer::aligned(period);
+        let mut watcher = timer.watcher();
+
+        for _ in 0..3 {
+            timer.tick();
+
+            let next = timer.deadline.delivery_time();
+            let distance = boundary_distance(next, period, Duration::ZERO);
+            assert!(
+                distance < Duration::from_millis(5),
+                "distance = {distance:?}"
+            );
+        }
+
+        assert_eq!(watcher.ticks_since_last(), 3);
+    }
+
+    #[cfg(all(target_os = "linux", feature = "timerfd"))]
+    #[test]
+    fn tick_fd() {
+        let mut timer = Timer::new(Duration::from_millis(20));
+        let mut watcher = timer.watcher();
+        let mut timerfd = timer.timerfd().unwrap();
+        let first = timer.deadline.delivery_time();
+
+        assert_eq!(timer.tick_fd(&mut timerfd).unwrap(), 0);
+        assert!(watcher.poll().is_none());
+
+        std::thread::sleep(Duration::from_millis(50));
+        let expirations = timer.tick_fd(&mut timerfd).unwrap();
+        assert!(expirations >= 2, "expirations = {expirations}");
+
+        let info = watcher.poll().unwrap();
+        assert_eq!(info.seq, expirations);
+        assert_eq!(info.missed, expirations - 1);
+        assert_eq!(
+            info.scheduled_at,
+            first + Duration::from_millis(20) * (expirations - 1) as u32
+        );
+        assert_eq!(timer.deadline.delivery_time(), timerfd.next_expiration());
+
+        #[cfg(feature = "stats")]
+        {
+            assert_eq!(timer.stats().count(), 1);
+            assert_eq!(timer.stats().bursts(), 0);
+        }
+    }
+
+    #[cfg(all(target_os = "linux", feature = "timerfd"))]
+    #[test]
+    fn tick_fd_rearm() {
+        let mut timer = Timer::new(Duration::from_secs(3600));
+        let mut watcher = timer.watcher();
+        let mut timerfd = timer.timerfd().unwrap();
+
+        // The timerfd follows the new period rather than waiting for the old one.
+        timer.set_period(Duration::from_millis(20));
+        assert_eq!(timerfd.period(), Some(Duration::from_millis(20)));
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(timer.tick_fd(&mut timerfd).unwrap() >= 2);
+        assert!(watcher.has_ticked());
+
+        timer.pause();
+        std::thread::sleep(Duration::from_millis(50));
+        timer.resume();
+        assert_eq!(timerfd.next_expiration(), timer.deadline.delivery_time());
+        assert_eq!(timer.tick_fd(&mut timerfd).unwrap(), 0, "stale expirations");
+
+        // An aligned timer is re-armed on the next wall-clock boundary on each tick.
+        let mut timer = Timer::aligned(Duration::from_millis(50));
+        let mut timerfd = timer.timerfd().unwrap();
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(timer.tick_fd(&mut timerfd).unwrap() >= 1);
+        assert_eq!(timerfd.next_expiration(), timer.deadline.delivery_time());
+    }
+
+    #[test]
+    fn set_period() {
+        let clock = ManualClock::new();
+        let mut timer = Timer::with_clock(Duration::from_millis(100), clock.clone());
+        assert_eq!(timer.period(), Duration::from_millis(100));
+
+        timer.tick();
+        assert_eq!(clock.elapsed(), Duration::from_millis(100));
+
+        timer.set_period(Duration::from_millis(20));
+        assert_eq!(timer.period(), Duration::from_millis(20));
+
+        for count in 1..=5 {
+            timer.tick();
+            assert_eq!(clock.elapsed(), Duration::from_millis(100 + 20 * count));
+        }
+
+        // Speeding the timer up late in a period doesn't burst through the missed ticks.
+        let clock = ManualClock::new();
+        let mut timer = Timer::with_clock(Duration::from_secs(1), clock.clone());
+        timer.tick();
+        clock.advance(Duration::from_millis(900));
+        timer.set_period(Duration::from_millis(100));
+
+        for count in 0..3 {
+            timer.tick();
+            assert_eq!(clock.elapsed(), Duration::from_millis(1900 + 100 * count));
+        }
+    }
+
+    #[test]
+    fn pause_resume() {
+        let clock = ManualClock::new();
+        let mut timer = Timer::with_clock(Duration::from_millis(100), clock.clone());
+        let mut watcher = timer.watcher();
+
+        timer.tick();
+        assert!(watcher.has_ticked());
+
+        clock.advance(Duration::from_millis(30));
+        timer.pause();
+        assert!(timer.is_paused());
+
+        timer.tick();
+        assert_eq!(clock.elapsed(), Duration::from_millis(200));
+        assert!(!watcher.has_ticked(), "paused timer shouldn't notify");
+
+        clock.advance(Duration::from_secs(10));
+        timer.resume();
+        assert!(!timer.is_paused());
+
+        let resumed_at = clock.elapsed();
+        timer.tick();
+        assert_eq!(clock.elapsed() - resumed_at, Duration::from_millis(70));
+        assert!(watcher.has_ticked());
+
+        timer.tick();
+        assert_eq!(clock.elapsed() - resumed_at, Duration::from_millis(170));
+    }
+
+    #[test]
+    fn tick_timestamps() {
+        let clock = ManualClock::new();
+        let start = clock.now();
+        let mut timer = Timer::with_clock(Duration::from_millis(100), clock.clone());
+        let mut watcher = timer.watcher();
+
+        timer.tick();
+        let info = watcher.poll().unwrap();
+        assert_eq!(info.scheduled_at - start, Duration::from_millis(100));
+        assert_eq!(info.fired_at, info.scheduled_at);
+
+        // A late tick is fired as soon as the timer wakes up.
+        clock.advance(Duration::from_millis(150));
+        timer.tick();
+        let info = watcher.poll().unwrap();
+        assert_eq!(info.scheduled_at - start, Duration::from_millis(200));
+        assert_eq!(info.fired_at - start, Duration::from_millis(250));
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn stats() {
+        let clock = ManualClock::new();
+        let mut timer = Timer::with_clock(Duration::from_millis(100), clock.clone());
+
+        clock.advance(Duration::from_millis(250));
+        for _ in 0..3 {
+            timer.tick();
+        }
+
+        let stats = timer.stats();
+        assert_eq!(stats.count(), 3);
+        assert_eq!(stats.max(), Duration::from_millis(150));
+        assert_eq!(stats.mean().as_micros(), 66_666);
+        assert_eq!(stats.bursts(), 2);
+
+        timer.reset_stats();
+        assert_eq!(timer.stats().count(), 0);
+    }
+
+    #[test]
+    fn payload() {
+        let mut timer =
+            Timer::with_clock_and_payload(Duration::from_millis(100), ManualClock::new(), 0);
+        let mut watcher = timer.watcher();
+        let mut clone = watcher.clone();
+        assert_eq!(watcher.value(), 0);
+        assert_eq!(clone.has_ticked_with(), None);
+
+        timer.tick_with(1);
+        timer.tick_with(2);
+        assert_eq!(watcher.value(), 2);
+
+        let info = watcher.poll().unwrap();
+        assert_eq!((info.seq, info.missed, info.value), (2, 1, 2));
+        assert_eq!(clone.has_ticked_with(), Some(2));
+        assert_eq!(clone.has_ticked_with(), None);
+
+        // Plain ticks broadcast the previous payload again.
+        timer.tick();
+        assert_eq!(watcher.poll().map(|info| info.value), Some(2));
+
+        timer.pause();
+        timer.tick_with(3);
+        assert_eq!(watcher.poll(), None);
+        assert_eq!(watcher.value(), 2);
+    }
+
+    #[test]
+    fn barrier() {
+        let mut timer = Timer::with_clock(Duration::from_millis(100), ManualClock::new());
+        timer.set_barrier(Some(Duration::from_secs(10)));
+
+        let workers: Vec<_> = (0..2)
+            .map(|_| {
+                let mut watcher = timer.watcher();
+
+                std::thread::spawn(move || {
+                    while watcher.wait().is_ok() {
+                        std::thread::sleep(Duration::from_millis(20));
+                        watcher.ack();
+                    }
+                })
+            })
+            .collect();
 
-        Self { state, prev_state }
-    }
-}
+        for _ in 0..3 {
+            let now = Instant::now();
+            timer.tick();
 
-/* ---------- */
+            assert!(now.elapsed() >= Duration::from_millis(20));
+            assert!(timer.late_watchers().is_empty());
+        }
 
-/// Inner state of the [`Timer`] and [`Watcher`] types.
-#[derive(Debug, Default, Clone)]
-struct State(Arc<AtomicBool>);
+        // A watcher that never acknowledges the ticks is reported once the timeout elapses.
+        let idle = timer.watcher();
+        timer.set_barrier(Some(Duration::from_millis(50)));
+        timer.tick();
+        assert_eq!(timer.late_watchers(), [idle.id()]);
 
-impl State {
-    /// Returns a new state with a default value.
-    #[inline]
-    fn new() -> Self {
-        Self::default()
-    }
+        // Dropped watchers don't take part in the barrier anymore.
+        drop(idle);
+        timer.tick();
+        assert!(timer.late_watchers().is_empty());
 
-    /// Flip the state's value.
-    #[inline]
-    fn toggle(&self) {
-        self.0.fetch_xor(true, Ordering::Release);
+        drop(timer);
+        for worker in workers {
+            worker.join().unwrap();
+        }
     }
 
-    /// Returns the state's inner value.
-    #[inline]
-    fn value(&self) -> bool {
-        self.0.load(Ordering::Acquire)
-    }
-}
+    #[test]
+    fn tick_clock() {
+        let clock = ManualClock::new();
+        let mut timer = Timer::with_clock(Duration::from_millis(100), clock.clone());
 
-#[cfg(test)]
-impl PartialEq<bool> for State {
-    #[inline]
-    fn eq(&self, other: &bool) -> bool {
-        self.value() == *other
+        for count in 1..=5 {
+            timer.tick();
+            assert_eq!(clock.elapsed(), Duration::from_millis(100 * count));
+        }
     }
 }
 
-/* ---------- */
-
 #[cfg(test)]
-mod state {
+mod handle {
     use super::*;
 
     #[test]
-    fn new() {
-        let new = State::new();
-        assert_eq!(new, false);
+    fn spawn() {
+        let handle = Timer::spawn(Duration::from_millis(10));
+        let mut watcher = handle.watcher();
+
+        let now = Instant::now();
+        for seq in 1..=3 {
+            let info = watcher.wait().unwrap();
+            assert_eq!((info.seq, info.missed), (seq, 0));
+            assert!(info.fired_at >= info.scheduled_at);
+        }
+        assert!(now.elapsed() >= Duration::from_millis(30));
+
+        assert!(!watcher.is_disconnected());
+        handle.stop().unwrap();
+        assert!(watcher.is_disconnected());
     }
 
     #[test]
-    fn toggle() {
-        let new = State::new();
-        assert_eq!(new, false);
+    fn builder() {
+        let timer = Timer::new(Duration::from_millis(10));
+        let handle = TimerBuilder::new()
+            .name("minuteurs-timer")
+            .stack_size(64 * 1024)
+            .spawn(timer)
+            .unwrap();
+        let mut watcher = handle.watcher();
 
-        new.toggle();
-        assert_eq!(new, true);
+        assert_eq!(handle.thread().name(), Some("minuteurs-timer"));
+        assert!(watcher.wait().is_ok());
+
+        drop(handle);
+        assert!(watcher.is_disconnected());
     }
-}
 
-#[cfg(test)]
-#[allow(clippy::module_inception)]
-mod timer {
-    use std::time::Instant;
+    #[test]
+    fn payload() {
+        let timer = Timer::with_payload(Duration::from_millis(10), 42u32);
+        let handle = TimerBuilder::new().spawn(timer).unwrap();
+        let mut watcher = handle.watcher();
 
-    use super::*;
+        assert_eq!(watcher.wait().map(|info| info.value), Ok(42));
+        assert_eq!(handle.watcher_named("payload").value(), 42);
+    }
 
     #[test]
-    fn tick_delay() {
-        let now = Instant::now();
-        let mut timer = Timer::new(Duration::from_millis(100));
-
-        for count in 0..5 {
-            timer.tick();
+    fn stop() {
+        let handle = Timer::spawn(Duration::from_secs(60));
+        let watcher = handle.watcher();
+        std::thread::sleep(Duration::from_millis(20));
 
-            let elapsed = now.elapsed();
-            assert!(
-                now.elapsed() >= Duration::from_millis(100 * count),
-                "elapsed = {elapsed:?}"
-            )
-        }
+        // The wait for the next tick is interrupted rather than awaited.
+        let now = Instant::now();
+        handle.stop().unwrap();
+        assert!(now.elapsed() < Duration::from_secs(1));
+        assert!(watcher.is_disconnected());
     }
 }
 
 #[cfg(test)]
 mod watcher {
+    use std::sync::atomic::AtomicBool;
     use std::time::Instant;
 
     use super::*;
+    use crate::ManualClock;
+
+    /// Returns a timer ticking every 100ms on a manual clock, along with the time it started at.
+    fn manual_timer() -> (Instant, Timer<(), ManualClock>) {
+        let clock = ManualClock::new();
+        (
+            clock.now(),
+            Timer::with_clock(Duration::from_millis(100), clock),
+        )
+    }
+
+    /// Returns the informations of the `seq`-th tick of a timer returned by [`manual_timer()`].
+    fn tick_info(start: Instant, seq: u64, missed: u64) -> TickInfo {
+        let at = start + Duration::from_millis(100 * seq);
+
+        TickInfo {
+            seq,
+            missed,
+            scheduled_at: at,
+            fired_at: at,
+            value: (),
+        }
+    }
 
     #[test]
     fn new() {
-        let mut timer = Timer::new(Duration::from_millis(100));
+        let mut timer = Timer::with_clock(Duration::from_millis(100), ManualClock::new());
         let mut watcher = timer.watcher();
 
         assert!(
@@ -192,7 +2001,7 @@ mod watcher {
 
     #[test]
     fn cloned() {
-        let mut timer = Timer::new(Duration::from_millis(100));
+        let mut timer = Timer::with_clock(Duration::from_millis(100), ManualClock::new());
 
         let mut watcher = timer.watcher();
         assert!(
@@ -220,6 +2029,356 @@ mod watcher {
         );
     }
 
+    #[test]
+    fn registry() {
+        let timer = Timer::with_clock(Duration::from_millis(100), ManualClock::new());
+        assert_eq!(timer.watcher_count(), 0);
+
+        let watcher = timer.watcher();
+        let encoder = timer.watcher_named("encoder");
+        let encoder_clone = encoder.clone();
+        assert_eq!(timer.watcher_count(), 3);
+
+        assert_eq!(watcher.name(), None);
+        assert_eq!(encoder.name(), Some("encoder"));
+        assert_eq!(encoder_clone.name(), Some("encoder"));
+        assert_ne!(encoder.id(), encoder_clone.id());
+        assert_eq!(timer.watcher_name(encoder.id()).as_deref(), Some("encoder"));
+
+        let id = encoder.id();
+        drop(encoder);
+        drop(watcher);
+        assert_eq!(timer.watcher_count(), 1);
+        assert_eq!(timer.watcher_name(id), None);
+    }
+
+    /// Returns whether or not `fd` is readable, without reading it.
+    #[cfg(all(target_os = "linux", feature = "eventfd"))]
+    fn is_readable(fd: BorrowedFd<'_>) -> bool {
+        use std::os::fd::AsRawFd;
+
+        let mut pollfd = libc::pollfd {
+            fd: fd.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: `pollfd` is a valid array of one descriptor.
+        unsafe { libc::poll(&mut pollfd, 1, 0) == 1 }
+    }
+
+    #[cfg(all(target_os = "linux", feature = "eventfd"))]
+    #[test]
+    fn event_fd() {
+        let (start, mut timer) = manual_timer();
+        let mut watcher = timer.watcher();
+        let mut watcher_every = timer.watcher_every(2);
+
+        // The ticks occuring before the eventfd's creation are signaled.
+        timer.tick();
+        assert!(is_readable(watcher.as_event_fd().unwrap()));
+        assert!(!is_readable(watcher_every.as_event_fd().unwrap()));
+
+        // Reading the eventfd consumes the ticks.
+        assert_eq!(watcher.read_event_fd(), Ok(Some(tick_info(start, 1, 0))));
+        assert!(!is_readable(watcher.as_event_fd().unwrap()));
+        assert!(!watcher.has_ticked());
+        assert_eq!(watcher.read_event_fd(), Ok(None));
+
+        // Consuming the ticks clears the eventfd.
+        timer.tick();
+        assert!(is_readable(watcher.as_event_fd().unwrap()));
+        assert!(is_readable(watcher_every.as_event_fd().unwrap()));
+        assert!(watcher.has_ticked());
+        assert!(!is_readable(watcher.as_event_fd().unwrap()));
+        assert_eq!(watcher_every.poll().unwrap().seq, 2);
+        assert!(!is_readable(watcher_every.as_event_fd().unwrap()));
+
+        timer.tick();
+        timer.tick();
+        assert_eq!(watcher.ticks_since_last(), 2);
+        assert!(!is_readable(watcher.as_event_fd().unwrap()));
+        assert!(watcher_every.wait().is_ok());
+        assert!(!is_readable(watcher_every.as_event_fd().unwrap()));
+
+        timer.tick();
+        drop(timer);
+        assert!(is_readable(watcher.as_event_fd().unwrap()));
+        assert_eq!(watcher.read_event_fd(), Ok(Some(tick_info(start, 5, 0))));
+        assert_eq!(watcher.read_event_fd(), Err(Disconnected));
+        assert!(is_readable(watcher.as_event_fd().unwrap()));
+        assert!(is_readable(watcher_every.as_event_fd().unwrap()));
+    }
+
+    #[test]
+    fn fresh() {
+        let mut timer = Timer::with_clock(Duration::from_millis(100), ManualClock::new());
+        let mut watcher = timer.watcher_named("encoder").divided(2);
+
+        timer.tick();
+        timer.tick();
+
+        let mut clone = watcher.clone();
+        let mut fresh = watcher.fresh();
+        assert_eq!(fresh.name(), Some("encoder"));
+        assert_eq!(
+            clone.ticks_since_last(),
+            1,
+            "clone should inherit the pending ticks"
+        );
+        assert_eq!(
+            fresh.ticks_since_last(),
+            0,
+            "fresh watcher shouldn't have pending ticks"
+        );
+
+        timer.tick();
+        timer.tick();
+        assert_eq!(watcher.ticks_since_last(), 2);
+        assert_eq!(fresh.ticks_since_last(), 1);
+    }
+
+    #[test]
+    fn missed_ticks() {
+        let (start, mut timer) = manual_timer();
+        let mut watcher = timer.watcher();
+        assert_eq!(watcher.poll(), None);
+
+        timer.tick();
+        timer.tick();
+        assert!(watcher.has_ticked(), "watcher should have been notified");
+        assert!(
+            !watcher.has_ticked(),
+            "watcher shouldn't have been notified twice"
+        );
+
+        timer.tick();
+        assert_eq!(watcher.ticks_since_last(), 1);
+        assert_eq!(watcher.ticks_since_last(), 0);
+
+        timer.tick();
+        timer.tick();
+        timer.tick();
+        assert_eq!(watcher.poll(), Some(tick_info(start, 6, 2)));
+        assert_eq!(watcher.poll(), None);
+    }
+
+    #[test]
+    fn divided() {
+        let (start, mut timer) = manual_timer();
+        let mut every_3 = timer.watcher_every(3);
+        let mut every_4 = timer.watcher().divided(4).with_phase(5);
+        let mut every_4_clone = every_4.clone();
+
+        let mut notified_3 = Vec::new();
+        let mut notified_4 = Vec::new();
+
+        for seq in 1..=12 {
+            timer.tick();
+
+            if every_3.has_ticked() {
+                notified_3.push(seq);
+            }
+
+            if every_4.has_ticked() {
+                notified_4.push(seq);
+            }
+        }
+
+        assert_eq!(notified_3, [3, 6, 9, 12]);
+        assert_eq!(notified_4, [1, 5, 9]);
+
+        for _ in 0..10 {
+            timer.tick();
+        }
+
+        // The divided watchers report the last tick they were notified of.
+        assert_eq!(every_3.poll(), Some(tick_info(start, 21, 2)));
+        assert_eq!(every_4.poll(), Some(tick_info(start, 21, 2)));
+        assert_eq!(every_4_clone.ticks_since_last(), 6);
+    }
+
+    #[test]
+    fn last_notified() {
+        assert_eq!(Divider::ONE.last_notified(5), 5);
+
+        let divider = Divider { n: 4, phase: 1 };
+        assert_eq!(divider.last_notified(0), 0);
+        assert_eq!(divider.last_notified(1), 1);
+        assert_eq!(divider.last_notified(4), 1);
+        assert_eq!(divider.last_notified(9), 9);
+        assert_eq!(divider.last_notified(12), 9);
+    }
+
+    #[test]
+    #[should_panic]
+    fn divided_by_zero() {
+        let timer = Timer::with_clock(Duration::from_millis(100), ManualClock::new());
+        let _ = timer.watcher_every(0);
+    }
+
+    #[test]
+    fn wait_divided() {
+        let (start, mut timer) = manual_timer();
+        let mut watcher = timer.watcher_every(5);
+
+        let watcher_thread = std::thread::spawn(move || watcher.wait());
+
+        for _ in 0..5 {
+            std::thread::sleep(Duration::from_millis(10));
+            timer.tick();
+        }
+
+        assert_eq!(watcher_thread.join().unwrap(), Ok(tick_info(start, 5, 0)));
+    }
+
+    #[test]
+    fn wait() {
+        let (start, mut timer) = manual_timer();
+        let mut watcher = timer.watcher();
+
+        let watcher_thread = std::thread::spawn(move || {
+            let info = watcher.wait();
+            (watcher, info)
+        });
+
+        std::thread::sleep(Duration::from_millis(50));
+        timer.tick();
+
+        let (mut watcher, info) = watcher_thread.join().unwrap();
+        assert_eq!(info, Ok(tick_info(start, 1, 0)));
+        assert!(!watcher.has_ticked(), "tick should have been consumed");
+
+        // Ticks occuring before the wait are reported right away.
+        timer.tick();
+        assert_eq!(watcher.wait(), Ok(tick_info(start, 2, 0)));
+    }
+
+    #[test]
+    fn wait_timeout() {
+        let (start, mut timer) = manual_timer();
+        let mut watcher = timer.watcher();
+
+        let now = Instant::now();
+        assert_eq!(
+            watcher.wait_timeout(Duration::from_millis(50)),
+            Err(WaitTimeoutError::Timeout)
+        );
+        assert!(now.elapsed() >= Duration::from_millis(50));
+
+        let watcher_thread =
+            std::thread::spawn(move || watcher.wait_timeout(Duration::from_secs(10)));
+
+        std::thread::sleep(Duration::from_millis(50));
+        timer.tick();
+        assert_eq!(
+            watcher_thread.join().unwrap(),
+            Ok(tick_info(start, 1, 0)),
+            "watcher should have been notified"
+        );
+    }
+
+    #[test]
+    fn disconnected() {
+        let (start, mut timer) = manual_timer();
+        let mut watcher = timer.watcher();
+        let mut watcher_clone = watcher.clone();
+        assert!(!watcher.is_disconnected());
+
+        let watcher_thread =
+            std::thread::spawn(move || watcher_clone.wait_timeout(Duration::from_secs(10)));
+
+        timer.tick();
+        timer.tick();
+        drop(timer);
+
+        assert!(watcher.is_disconnected());
+        assert_eq!(watcher.wait(), Ok(tick_info(start, 2, 1)));
+        assert_eq!(watcher.wait(), Err(Disconnected));
+        assert_eq!(
+            watcher.wait_timeout(Duration::from_secs(10)),
+            Err(WaitTimeoutError::Disconnected)
+        );
+
+        assert!(watcher_thread.join().unwrap().is_ok());
+    }
+
+    #[test]
+    fn disconnected_on_panic() {
+        let (start, mut timer) = manual_timer();
+        let mut watcher = timer.watcher();
+
+        let timer_thread = std::thread::spawn(move || {
+            timer.tick();
+            panic!("the timer's thread panicked");
+        });
+
+        assert_eq!(watcher.wait(), Ok(tick_info(start, 1, 0)));
+        assert_eq!(watcher.wait(), Err(Disconnected));
+        assert!(timer_thread.join().is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn stream() {
+        use futures_core::Stream;
+
+        use crate::testing::Counter;
+
+        let (start, mut timer) = manual_timer();
+        let mut watcher = timer.watcher();
+
+        let counter = Arc::new(Counter::default());
+        let waker = Waker::from(Arc::clone(&counter));
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(Pin::new(&mut watcher).poll_next(&mut cx), Poll::Pending);
+        assert_eq!(Pin::new(&mut watcher).poll_next(&mut cx), Poll::Pending);
+
+        timer.tick();
+        assert_eq!(counter.count(), 1);
+        assert_eq!(
+            Pin::new(&mut watcher).poll_next(&mut cx),
+            Poll::Ready(Some(tick_info(start, 1, 0)))
+        );
+
+        timer.tick();
+        assert_eq!(counter.count(), 1, "no waker registered");
+        assert_eq!(
+            Pin::new(&mut watcher).poll_next(&mut cx),
+            Poll::Ready(Some(tick_info(start, 2, 0)))
+        );
+
+        assert_eq!(Pin::new(&mut watcher).poll_next(&mut cx), Poll::Pending);
+        drop(timer);
+        assert_eq!(counter.count(), 2);
+        assert_eq!(Pin::new(&mut watcher).poll_next(&mut cx), Poll::Ready(None));
+    }
+
+    #[test]
+    fn payload_sync() {
+        let mut timer =
+            Timer::with_clock_and_payload(Duration::from_millis(100), ManualClock::new(), 0);
+        let mut watcher = timer.watcher();
+
+        let watcher_thread = std::thread::spawn(move || {
+            let mut last = 0;
+
+            while let Ok(info) = watcher.wait() {
+                assert_eq!(info.value, info.seq, "payload doesn't match its tick");
+                last = info.value;
+            }
+
+            last
+        });
+
+        for seq in 1..=1000 {
+            timer.tick_with(seq);
+        }
+
+        drop(timer);
+        assert_eq!(watcher_thread.join().unwrap(), 1000);
+    }
+
     #[test]
     fn thread_sync() {
         let stop = Arc::new(AtomicBool::default());
@@ -259,3 +2418,90 @@ mod watcher {
         );
     }
 }
+
+#[cfg(all(test, loom))]
+mod loom_model {
+    use loom::thread;
+
+    use super::*;
+    use crate::ManualClock;
+
+    #[test]
+    fn tick_visibility() {
+        loom::model(|| {
+            let mut timer =
+                Timer::with_clock_and_payload(Duration::from_millis(100), ManualClock::new(), 0);
+            let mut watcher = timer.watcher();
+
+            // The watcher must never miss the wake up, nor see the tick without its payload.
+            let watcher_thread = thread::spawn(move || {
+                let info = watcher.wait().unwrap();
+                (info.seq, info.value, watcher.value())
+            });
+
+            timer.tick_with(42);
+            assert_eq!(watcher_thread.join().unwrap(), (1, 42, 42));
+        });
+    }
+
+    #[test]
+    fn shutdown() {
+        loom::model(|| {
+            let mut timer = Timer::with_clock(Duration::from_millis(100), ManualClock::new());
+            let mut watcher = timer.watcher();
+
+            // The tick must be reported before the disconnection, which must wake the watcher up.
+            let watcher_thread = thread::spawn(move || {
+                let mut ticks = 0;
+                while watcher.wait().is_ok() {
+                    ticks += 1;
+                }
+
+                ticks
+            });
+
+            timer.tick();
+            drop(timer);
+            assert_eq!(watcher_thread.join().unwrap(), 1);
+        });
+    }
+
+    #[test]
+    fn barrier() {
+        loom::model(|| {
+            let mut timer = Timer::with_clock(Duration::from_millis(100), ManualClock::new());
+            let mut watcher = timer.watcher();
+            timer.set_barrier(Some(Duration::from_secs(3600)));
+
+            let watcher_thread = thread::spawn(move || {
+                watcher.wait().unwrap();
+                watcher.ack();
+            });
+
+            // The timer must not move on before the watcher acknowledged the tick.
+            timer.tick();
+            assert!(timer.late_watchers().is_empty());
+            watcher_thread.join().unwrap();
+        });
+    }
+
+    #[test]
+    fn clone() {
+        loom::model(|| {
+            let mut timer = Timer::with_clock(Duration::from_millis(100), ManualClock::new());
+            let watcher = timer.watcher();
+
+            // Whenever the tick lands, the clone must see exactly the same ticks as its source.
+            let watcher_thread = thread::spawn(move || {
+                let clone = watcher.clone();
+                (watcher, clone)
+            });
+
+            timer.tick();
+            let (mut watcher, mut clone) = watcher_thread.join().unwrap();
+
+            assert_eq!(watcher.ticks_since_last(), 1);
+            assert_eq!(clone.ticks_since_last(), 1);
+        });
+    }
+}
@@ -0,0 +1,341 @@
+//! The [`TimerFd`] implementation.
+
+use std::io::{Error as IoError, ErrorKind, Result as IoResult};
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
+use std::time::{Duration, Instant};
+
+use crate::clock::monotonic_now;
+use crate::deadline::{checked_delivery_time, mul_duration};
+use crate::{Clock, Deadline};
+
+/* ---------- */
+
+/// A periodic timer backed by a Linux `timerfd`, for event loops that can't afford a thread per timer.
+///
+/// The file descriptor is non-blocking and becomes readable when the timer expires, so it can be
+/// registered in epoll, mio or any other event loop through [`AsRawFd`] or [`AsFd`]. The expirations
+/// are then consumed with [`TimerFd::read_expirations()`].
+///
+/// The timer is armed with `TFD_TIMER_ABSTIME` on the `CLOCK_MONOTONIC` clock, so its expirations
+/// don't drift no matter how late they are read.
+#[derive(Debug)]
+pub struct TimerFd {
+    /// The state shared with the [`Timer`](crate::Timer) the timer was created by, if any.
+    inner: Arc<TimerFdInner>,
+}
+
+impl TimerFd {
+    /// Returns a new timer that expires every `period`, starting one period from now.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `timerfd` couldn't be created or armed.
+    pub fn new(period: Duration) -> IoResult<Self> {
+        let now = Instant::now();
+        Self::arm(checked_delivery_time(now, period), period)
+    }
+
+    /// Returns a new timer that expires at the same times as `deadline`, that is once for a
+    /// deadline triggered only once or periodically for a repeatable one.
+    ///
+    /// The delivery time of the deadline is read as an instant of the system's monotonic clock,
+    /// whatever its [`Clock`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `timerfd` couldn't be created or armed.
+    pub fn from_deadline<C: Clock>(deadline: &Deadline<C>) -> IoResult<Self> {
+        Self::arm(
+            deadline.delivery_time(),
+            deadline.period().unwrap_or_default(),
+        )
+    }
+
+    /// Returns a new timer first expiring at `delivery_time`, then every `period` unless
+    /// `period` is null.
+    fn arm(delivery_time: Instant, period: Duration) -> IoResult<Self> {
+        // SAFETY: the flags are valid and the returned descriptor is checked right away.
+        let fd = unsafe {
+            libc::timerfd_create(
+                libc::CLOCK_MONOTONIC,
+                libc::TFD_NONBLOCK | libc::TFD_CLOEXEC,
+            )
+        };
+
+        if fd < 0 {
+            return Err(IoError::last_os_error());
+        }
+
+        // SAFETY: `fd` is a freshly created descriptor owned by nobody else.
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        let timer = Self {
+            inner: Arc::new(TimerFdInner {
+                fd,
+                schedule: Mutex::new(Schedule {
+                    period,
+                    next: delivery_time,
+                }),
+            }),
+        };
+
+        timer.inner.rearm(delivery_time, period)?;
+        Ok(timer)
+    }
+
+    /// Re-arms the timer, see [`TimerFdInner::rearm()`].
+    #[inline]
+    pub(crate) fn rearm(&self, delivery_time: Instant, period: Duration) -> IoResult<()> {
+        self.inner.rearm(delivery_time, period)
+    }
+
+    /// Returns a weak handle to re-arm the timer with, see [`TimerFdInner::rearm()`].
+    #[inline]
+    pub(crate) fn downgrade(&self) -> Weak<TimerFdInner> {
+        Arc::downgrade(&self.inner)
+    }
+
+    /// Returns the period of the timer, or `None` if it expires only once.
+    #[inline]
+    pub fn period(&self) -> Option<Duration> {
+        let period = self.inner.schedule().period;
+        (!period.is_zero()).then_some(period)
+    }
+
+    /// Returns the time of the next expiration.
+    #[inline]
+    pub fn next_expiration(&self) -> Instant {
+        self.inner.schedule().next
+    }
+
+    /// Returns the number of times the timer expired since the last read, without blocking.
+    ///
+    /// This is the overrun count of the kernel, which plays the same role as the tick count of a
+    /// [`Timer`](crate::Timer): it is `0` if the timer didn't expire and greater than `1` if some
+    /// expirations were missed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `timerfd` couldn't be read.
+    pub fn read_expirations(&mut self) -> IoResult<u64> {
+        let mut expirations = 0u64;
+
+        // Locking the schedule first guarantees that the timer isn't re-armed between the read
+        // and the update of the schedule.
+        let mut schedule = self.inner.schedule();
+
+        loop {
+            // SAFETY: the buffer is valid for 8 bytes, the size of the overrun count.
+            let ret = unsafe {
+                libc::read(
+                    self.inner.fd.as_raw_fd(),
+                    (&mut expirations as *mut u64).cast(),
+                    size_of::<u64>(),
+                )
+            };
+
+            if ret >= 0 {
+                break;
+            }
+
+            let err = IoError::last_os_error();
+            match err.kind() {
+                ErrorKind::Interrupted => continue,
+                ErrorKind::WouldBlock => return Ok(0),
+                _ => return Err(err),
+            }
+        }
+
+        schedule.next =
+            checked_delivery_time(schedule.next, mul_duration(schedule.period, expirations));
+        Ok(expirations)
+    }
+
+    /// Blocks the current thread until the timer expires and returns the number of times it
+    /// expired since the last read, see [`TimerFd::read_expirations()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `timerfd` couldn't be polled or read.
+    pub fn wait(&mut self) -> IoResult<u64> {
+        loop {
+            let expirations = self.read_expirations()?;
+
+            if expirations > 0 {
+                return Ok(expirations);
+            }
+
+            let mut pollfd = libc::pollfd {
+                fd: self.inner.fd.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+
+            // SAFETY: `pollfd` is a valid array of one descriptor.
+            let ret = unsafe { libc::poll(&mut pollfd, 1, -1) };
+
+            if ret < 0 {
+                let err = IoError::last_os_error();
+
+                if err.kind() != ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            }
+        }
+    }
+}
+
+impl AsRawFd for TimerFd {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.fd.as_raw_fd()
+    }
+}
+
+impl AsFd for TimerFd {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.fd.as_fd()
+    }
+}
+
+/* ---------- */
+
+/// The state of a [`TimerFd`], shared with the [`Timer`](crate::Timer) it was created by so
+/// the timer can re-arm it when its schedule changes.
+#[derive(Debug)]
+pub(crate) struct TimerFdInner {
+    /// The `timerfd` file descriptor.
+    fd: OwnedFd,
+
+    /// The schedule the timer is armed with.
+    schedule: Mutex<Schedule>,
+}
+
+impl TimerFdInner {
+    /// Arms the timer so it first expires at `delivery_time`, then every `period` unless
+    /// `period` is null, discarding the pending expirations.
+    pub(crate) fn rearm(&self, delivery_time: Instant, period: Duration) -> IoResult<()> {
+        let mut schedule = self.schedule();
+
+        // A null expiration disarms the timer, so an expired delivery time is clamped
+        // to the smallest possible one, which expires right away.
+        let now = Instant::now();
+        let target = match delivery_time.checked_duration_since(now) {
+            Some(remaining) => monotonic_now() + remaining,
+            None => monotonic_now().saturating_sub(now - delivery_time),
+        };
+        let spec = libc::itimerspec {
+            it_interval: timespec(period),
+            it_value: timespec(target.max(Duration::from_nanos(1))),
+        };
+
+        // SAFETY: `fd` is a valid timerfd and `spec` a valid itimerspec, the previous
+        // setting is not requested.
+        let ret = unsafe {
+            libc::timerfd_settime(
+                self.fd.as_raw_fd(),
+                libc::TFD_TIMER_ABSTIME,
+                &spec,
+                std::ptr::null_mut(),
+            )
+        };
+
+        if ret < 0 {
+            return Err(IoError::last_os_error());
+        }
+
+        *schedule = Schedule {
+            period,
+            next: delivery_time,
+        };
+        Ok(())
+    }
+
+    /// Locks the schedule of the timer.
+    #[inline]
+    fn schedule(&self) -> MutexGuard<'_, Schedule> {
+        self.schedule.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The schedule a [`TimerFd`] is armed with.
+#[derive(Debug)]
+struct Schedule {
+    /// The period between each expiration, [`Duration::ZERO`] if the timer expires only once.
+    period: Duration,
+
+    /// The time of the next expiration.
+    next: Instant,
+}
+
+/* ---------- */
+
+/// Converts `dur` into a timespec.
+#[inline]
+fn timespec(dur: Duration) -> libc::timespec {
+    libc::timespec {
+        tv_sec: dur.as_secs().try_into().unwrap_or(libc::time_t::MAX),
+        tv_nsec: dur.subsec_nanos() as libc::c_long,
+    }
+}
+
+/* ---------- */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wait() {
+        let now = Instant::now();
+        let mut timer = TimerFd::new(Duration::from_millis(50)).unwrap();
+        assert_eq!(timer.period(), Some(Duration::from_millis(50)));
+        assert_eq!(timer.read_expirations().unwrap(), 0);
+
+        assert_eq!(timer.wait().unwrap(), 1);
+        assert!(now.elapsed() >= Duration::from_millis(50));
+        assert_eq!(timer.read_expirations().unwrap(), 0);
+
+        assert_eq!(timer.wait().unwrap(), 1);
+        assert!(now.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn new_max() {
+        // An overflowing period is clamped rather than panicking.
+        let mut timer = TimerFd::new(Duration::MAX).unwrap();
+        assert_eq!(timer.period(), Some(Duration::MAX));
+        assert!(timer.next_expiration() > Instant::now() + Duration::from_secs(86400 * 365));
+        assert_eq!(timer.read_expirations().unwrap(), 0);
+    }
+
+    #[test]
+    fn overruns() {
+        let mut timer = TimerFd::new(Duration::from_millis(20)).unwrap();
+        let first = timer.next_expiration();
+
+        std::thread::sleep(Duration::from_millis(70));
+        let expirations = timer.read_expirations().unwrap();
+        assert!(expirations >= 3, "expirations = {expirations}");
+        assert_eq!(
+            timer.next_expiration(),
+            first + Duration::from_millis(20) * expirations as u32
+        );
+    }
+
+    #[test]
+    fn from_deadline() {
+        let mut timer = TimerFd::from_deadline(&Deadline::once(Duration::from_millis(20))).unwrap();
+        assert_eq!(timer.period(), None);
+        assert_eq!(timer.wait().unwrap(), 1);
+
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(timer.read_expirations().unwrap(), 0);
+
+        // An expired deadline expires right away.
+        let deadline = Deadline::at(Instant::now() - Duration::from_secs(1));
+        let mut timer = TimerFd::from_deadline(&deadline).unwrap();
+        assert_eq!(timer.read_expirations().unwrap(), 1);
+    }
+}
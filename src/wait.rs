@@ -0,0 +1,160 @@
+//! The [`WaitStrategy`] implementation.
+
+use std::time::{Duration, Instant};
+
+use crate::{CancelToken, Cancelled, Clock};
+
+/* ---------- */
+
+/// Defines how [`Deadline`](crate::Deadline)s and [`Timer`](crate::Timer)s block the thread
+/// until their delivery time.
+///
+/// Strategies other than [`WaitStrategy::Sleep`] poll their [`Clock`] until the delivery time
+/// is reached, and thus require a clock that moves forward on its own.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WaitStrategy {
+    /// Puts the thread to sleep until the delivery time.
+    ///
+    /// This is the cheapest strategy, but the thread usually wakes up tens of microseconds late.
+    #[default]
+    Sleep,
+    /// Busy-spins until the delivery time.
+    ///
+    /// This is the most precise strategy, at the cost of a core running at 100%.
+    Spin,
+    /// Yields the thread to the scheduler until the delivery time.
+    Yield,
+    /// Sleeps until `margin` before the delivery time, then busy-spins until the delivery time.
+    ///
+    /// This gives most of the precision of [`WaitStrategy::Spin`] while only spinning for `margin`.
+    Hybrid {
+        /// The time spent spinning before the delivery time.
+        margin: Duration,
+    },
+}
+
+impl WaitStrategy {
+    /// Blocks the current thread until `clock` reaches `deadline`.
+    #[inline]
+    pub(crate) fn wait_until(&self, clock: &impl Clock, deadline: Instant) {
+        match self {
+            Self::Sleep => clock.sleep_until(deadline),
+            Self::Spin => spin_until(clock, deadline),
+            Self::Yield => {
+                while clock.now() < deadline {
+                    std::thread::yield_now()
+                }
+            }
+            Self::Hybrid { margin } => {
+                if let Some(wake_time) = deadline.checked_sub(*margin) {
+                    clock.sleep_until(wake_time);
+                }
+
+                spin_until(clock, deadline)
+            }
+        }
+    }
+
+    /// Blocks the current thread until `clock` reaches `deadline` or `token` is cancelled.
+    ///
+    /// The sleeps are done through [`Clock::sleep_until_cancellable()`] so they are interrupted
+    /// at once, while the token is checked between each iteration of the busy loops.
+    pub(crate) fn wait_until_cancellable(
+        &self,
+        clock: &impl Clock,
+        deadline: Instant,
+        token: &CancelToken,
+    ) -> Result<(), Cancelled> {
+        match self {
+            Self::Sleep => clock.sleep_until_cancellable(deadline, token),
+            Self::Spin => spin_until_cancellable(clock, deadline, token, std::hint::spin_loop),
+            Self::Yield => spin_until_cancellable(clock, deadline, token, std::thread::yield_now),
+            Self::Hybrid { margin } => {
+                if let Some(wake_time) = deadline.checked_sub(*margin) {
+                    clock.sleep_until_cancellable(wake_time, token)?;
+                }
+
+                spin_until_cancellable(clock, deadline, token, std::hint::spin_loop)
+            }
+        }
+    }
+}
+
+/* ---------- */
+
+/// Calls `relax` until `clock` reaches `deadline` or `token` is cancelled.
+#[inline]
+fn spin_until_cancellable(
+    clock: &impl Clock,
+    deadline: Instant,
+    token: &CancelToken,
+    relax: fn(),
+) -> Result<(), Cancelled> {
+    while clock.now() < deadline {
+        if token.is_cancelled() {
+            return Err(Cancelled);
+        }
+
+        relax()
+    }
+
+    Ok(())
+}
+
+/// Busy-spins until `clock` reaches `deadline`.
+#[inline]
+fn spin_until(clock: &impl Clock, deadline: Instant) {
+    while clock.now() < deadline {
+        std::hint::spin_loop()
+    }
+}
+
+/* ---------- */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ManualClock, SystemClock};
+
+    /// Waits for 20ms with `strategy` and returns how late the thread woke up.
+    fn lateness(strategy: WaitStrategy) -> Duration {
+        let deadline = Instant::now() + Duration::from_millis(20);
+        strategy.wait_until(&SystemClock, deadline);
+        deadline.elapsed()
+    }
+
+    #[test]
+    fn sleep() {
+        let clock = ManualClock::new();
+        let deadline = clock.now() + Duration::from_secs(1);
+
+        WaitStrategy::Sleep.wait_until(&clock, deadline);
+        assert_eq!(clock.now(), deadline);
+    }
+
+    #[test]
+    fn spin() {
+        let late = lateness(WaitStrategy::Spin);
+        assert!(late < Duration::from_millis(10), "late = {late:?}");
+    }
+
+    #[test]
+    fn yields() {
+        let late = lateness(WaitStrategy::Yield);
+        assert!(late < Duration::from_millis(10), "late = {late:?}");
+    }
+
+    #[test]
+    fn hybrid() {
+        let late = lateness(WaitStrategy::Hybrid {
+            margin: Duration::from_millis(2),
+        });
+        assert!(late < Duration::from_millis(10), "late = {late:?}");
+
+        // A margin larger than the remaining time spins right away.
+        let late = lateness(WaitStrategy::Hybrid {
+            margin: Duration::MAX,
+        });
+        assert!(late < Duration::from_millis(10), "late = {late:?}");
+    }
+}